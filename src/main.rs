@@ -1,108 +1,272 @@
-use crate::types::person::{Person, Preference};
-use crate::types::timetable::{Timetable, TurnHours, Turn, Day};
-
-mod types;
-
-fn main() {
-    let mut timetable = Timetable::new(); //load default values
-
-    let mut people = Vec::with_capacity(7usize);
-
-    people.push(Person::with_preferences("Andrea", "Bonvissuto", vec![
-        Preference::new(Day::Mon, Turn::Morning),
-        Preference::new(Day::Tue, Turn::Morning),
-        Preference::new(Day::Wed, Turn::Morning),
-        Preference::new(Day::Thu, Turn::Morning),
-        Preference::new(Day::Fri, Turn::Morning),
-        //afternoon
-        Preference::new(Day::Mon, Turn::Afternoon),
-        // Preference::new(Day::Tue, Turn::Afternoon),
-        // Preference::new(Day::Wed, Turn::Afternoon),
-        Preference::new(Day::Thu, Turn::Afternoon),
-        // Preference::new(Day::Fri, Turn::Afternoon),
-    ], 41.0));
-    people.push(Person::with_preferences("Luca", "De Candia", vec![
-        // Preference::new(Day::Mon, Turn::Morning),
-        // Preference::new(Day::Tue, Turn::Morning),
-        Preference::new(Day::Wed, Turn::Morning),
-        // Preference::new(Day::Thu, Turn::Morning),
-        Preference::new(Day::Fri, Turn::Morning),
-        //afternoon
-        // Preference::new(Day::Mon, Turn::Afternoon),
-        // Preference::new(Day::Tue, Turn::Afternoon),
-        // Preference::new(Day::Wed, Turn::Afternoon),
-        Preference::new(Day::Thu, Turn::Afternoon),
-        // Preference::new(Day::Fri, Turn::Afternoon),
-    ], 36.0));
-    people.push(Person::with_preferences("Daniele", "De Rossi", vec![
-        // Preference::new(Day::Mon, Turn::Morning),
-        // Preference::new(Day::Tue, Turn::Morning),
-        // Preference::new(Day::Wed, Turn::Morning),
-        Preference::new(Day::Thu, Turn::Morning),
-        // Preference::new(Day::Fri, Turn::Morning),
-        //afternoon
-        // Preference::new(Day::Mon, Turn::Afternoon),
-        // Preference::new(Day::Tue, Turn::Afternoon),
-        // Preference::new(Day::Wed, Turn::Afternoon),
-        // Preference::new(Day::Thu, Turn::Afternoon),
-        Preference::new(Day::Fri, Turn::Afternoon),
-    ], 28.0));
-    people.push(Person::with_preferences("Giovanni", "Giunta", vec![
-        // Preference::new(Day::Mon, Turn::Morning),
-        // Preference::new(Day::Tue, Turn::Morning),
-        // Preference::new(Day::Wed, Turn::Morning),
-        Preference::new(Day::Thu, Turn::Morning),
-        // Preference::new(Day::Fri, Turn::Morning),
-        //afternoon
-        // Preference::new(Day::Mon, Turn::Afternoon),
-        // Preference::new(Day::Tue, Turn::Afternoon),
-        // Preference::new(Day::Wed, Turn::Afternoon),
-        // Preference::new(Day::Thu, Turn::Afternoon),
-        Preference::new(Day::Fri, Turn::Afternoon),
-    ], 37.0));
-    people.push(Person::with_preferences("Vincenzo", "Miccichè", vec![
-        // Preference::new(Day::Mon, Turn::Morning),
-        Preference::new(Day::Tue, Turn::Morning),
-        // Preference::new(Day::Wed, Turn::Morning),
-        // Preference::new(Day::Thu, Turn::Morning),
-        // Preference::new(Day::Fri, Turn::Morning),
-        //afternoon
-        // Preference::new(Day::Mon, Turn::Afternoon),
-        // Preference::new(Day::Tue, Turn::Afternoon),
-        Preference::new(Day::Wed, Turn::Afternoon),
-        // Preference::new(Day::Thu, Turn::Afternoon),
-        // Preference::new(Day::Fri, Turn::Afternoon),
-    ], 32.0));
-    people.push(Person::with_all("Niccolò", "Querini Squillari", vec![
-        // Preference::new(Day::Mon, Turn::Morning),
-        // Preference::new(Day::Tue, Turn::Morning),
-        Preference::new(Day::Wed, Turn::Morning),
-        // Preference::new(Day::Thu, Turn::Morning),
-        // Preference::new(Day::Fri, Turn::Morning),
-        //afternoon
-        // Preference::new(Day::Mon, Turn::Afternoon),
-        // Preference::new(Day::Tue, Turn::Afternoon),
-        // Preference::new(Day::Wed, Turn::Afternoon),
-        // Preference::new(Day::Thu, Turn::Afternoon),
-        // Preference::new(Day::Fri, Turn::Afternoon),
-    ], 22.0, 150.0));
-    people.push(Person::with_preferences("Domenico Elia", "Sabella", vec![
-        // Preference::new(Day::Mon, Turn::Morning),
-        Preference::new(Day::Tue, Turn::Morning),
-        // Preference::new(Day::Wed, Turn::Morning),
-        Preference::new(Day::Thu, Turn::Morning),
-        Preference::new(Day::Fri, Turn::Morning),
-        //afternoon
-        // Preference::new(Day::Mon, Turn::Afternoon),
-        Preference::new(Day::Tue, Turn::Afternoon),
-        Preference::new(Day::Wed, Turn::Afternoon),
-        Preference::new(Day::Thu, Turn::Afternoon),
-        Preference::new(Day::Fri, Turn::Afternoon),
-    ], 39.0));
-
-
-    timetable.add_people(people);
-    //now calc the timetable and print
-
-    timetable.calc();
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use orario_borsisti::types::constraint::NoConsecutiveShifts;
+use orario_borsisti::types::error::ScheduleError;
+use orario_borsisti::types::export::{html, ical, json, spreadsheet, terminal};
+use orario_borsisti::types::forms::{self, FormMapping};
+use orario_borsisti::types::loader::{self, TimetableFile};
+use orario_borsisti::types::timetable::Timetable;
+
+///weekly timetable of the scholarship students, from their preferences
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    ///compute the timetable of an input file (.json, .toml or .csv)
+    Solve {
+        input: PathBuf,
+        #[command(flatten)]
+        model: ModelArgs,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    ///check an input file for problems before solving, fails when there are any
+    Validate {
+        input: PathBuf,
+        #[command(flatten)]
+        model: ModelArgs,
+    },
+    ///render again the result saved by `solve --format json`
+    Report {
+        result: PathBuf,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    ///add the hours of a result saved by `solve --format json` to the worked hours,
+    ///writing the input (.json or .toml) of the following weeks
+    Ledger {
+        result: PathBuf,
+        next_input: PathBuf,
+    },
+    ///read the CSV export of an availability form, writing an input file (.json or .toml)
+    Import {
+        form: PathBuf,
+        input: PathBuf,
+        #[command(flatten)]
+        columns: FormArgs,
+    },
+}
+
+#[derive(Args)]
+struct ModelArgs {
+    ///first ISO week to schedule, e.g. 2026-W15
+    #[arg(long)]
+    week: Option<String>,
+    ///weeks planned together
+    #[arg(long)]
+    weeks: Option<usize>,
+    ///minimum weekly hours of everybody
+    #[arg(long)]
+    min_hours: Option<f64>,
+    ///maximum weekly hours of everybody
+    #[arg(long)]
+    max_hours: Option<f64>,
+    ///hours a person may work past their contract
+    #[arg(long)]
+    max_overrun: Option<f64>,
+    ///at most one shift per day for every person
+    #[arg(long)]
+    no_consecutive_shifts: bool,
+}
+
+#[derive(Args)]
+struct FormArgs {
+    ///input file whose turns, days, config and people the form adds to
+    #[arg(long)]
+    base: Option<PathBuf>,
+    #[arg(long, default_value = "Nome")]
+    name_column: String,
+    #[arg(long, default_value = "Cognome")]
+    surname_column: String,
+    #[arg(long)]
+    worked_hours_column: Option<String>,
+    #[arg(long)]
+    tot_hours_column: Option<String>,
+}
+
+#[derive(Args)]
+struct OutputArgs {
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    ///file (json, html, xlsx) or directory (csv, ics) to write, stdout when missing where possible
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, PartialEq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Html,
+    Csv,
+    Xlsx,
+    Ics,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Solve { input, model, output } => solve(&input, &model, &output),
+        Command::Validate { input, model } => validate(&input, &model),
+        Command::Report { result, output } => report(&result, &output),
+        Command::Ledger { result, next_input } => ledger(&result, &next_input),
+        Command::Import { form, input, columns } => import(&form, &input, &columns),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn load(input: &Path, model: &ModelArgs) -> Result<Timetable, ScheduleError> {
+    let mut timetable = loader::load(input)?;
+    if let Some(week) = &model.week {
+        let invalid = || ScheduleError::InvalidInput { source: week.clone(), reason: "expected a week like 2026-W15".to_string() };
+        let (year, number) = week.split_once("-W").ok_or_else(invalid)?;
+        timetable.set_week(year.parse().map_err(|_| invalid())?, number.parse().map_err(|_| invalid())?)?;
+    }
+    if let Some(weeks) = model.weeks {
+        timetable.set_weeks(weeks);
+    }
+    let mut config = timetable.config;
+    config.min_hours_per_week = model.min_hours.unwrap_or(config.min_hours_per_week);
+    config.max_hours_per_week = model.max_hours.unwrap_or(config.max_hours_per_week);
+    config.max_overrun_hours = model.max_overrun.unwrap_or(config.max_overrun_hours);
+    timetable.set_config(config);
+    if model.no_consecutive_shifts {
+        timetable.add_constraint(NoConsecutiveShifts);
+    }
+    Ok(timetable)
+}
+
+fn solve(input: &Path, model: &ModelArgs, output: &OutputArgs) -> Result<bool, ScheduleError> {
+    let mut timetable = load(input, model)?;
+    let result = timetable.diagnose().and_then(|issues| {
+        //on stderr so that the output stays parsable
+        for issue in issues.iter() {
+            eprintln!("WARNING: {}", issue);
+        }
+        timetable.calc().map(|_| ())
+    });
+    match result {
+        Ok(()) => {
+            render(&timetable, output)?;
+            Ok(true)
+        }
+        //a failed run is still a json document for the scripts reading it
+        Err(e) if output.format == Format::Json => {
+            eprintln!("{}", e);
+            write(output, &format!("{}\n", json::to_string(&json::error_output(&timetable, &e))?))?;
+            Ok(false)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn validate(input: &Path, model: &ModelArgs) -> Result<bool, ScheduleError> {
+    let timetable = load(input, model)?;
+    let issues = timetable.diagnose()?;
+    for issue in issues.iter() {
+        println!("{}", issue);
+    }
+    for status in timetable.deadline_report()?.iter().filter(|s| s.is_reachable()) {
+        let person = &timetable.people[status.person];
+        println!("{} {} needs {:.1}h per week to finish by {}", person.name, person.surname,
+                 status.pace(), status.deadline);
+    }
+    if issues.is_empty() {
+        println!("{} people, no problems found", timetable.people.len());
+    }
+    Ok(issues.is_empty())
+}
+
+fn report(result: &Path, output: &OutputArgs) -> Result<bool, ScheduleError> {
+    let content = fs::read_to_string(result)
+        .map_err(|e| ScheduleError::InvalidInput { source: result.display().to_string(), reason: e.to_string() })?;
+    render(&json::read(&content)?, output)?;
+    Ok(true)
+}
+
+fn ledger(result: &Path, next_input: &Path) -> Result<bool, ScheduleError> {
+    let content = fs::read_to_string(result)
+        .map_err(|e| ScheduleError::InvalidInput { source: result.display().to_string(), reason: e.to_string() })?;
+    let mut output = json::parse(&content)?;
+    let hours: Vec<f64> = output.people.iter().map(|p| p.hours).collect();
+    for (person, h) in output.input.people.iter().zip(hours.iter()) {
+        println!("{} {}: {}h + {}h = {}h of {}h", person.name, person.surname,
+                 person.worked_hours, h, person.worked_hours + h, person.tot_hours);
+    }
+    output.input.advance(&hours)?;
+    loader::save(&output.input, next_input)?;
+    println!("written {}", next_input.display());
+    Ok(true)
+}
+
+fn import(form: &Path, input: &Path, columns: &FormArgs) -> Result<bool, ScheduleError> {
+    let mut timetable = match &columns.base {
+        Some(base) => loader::load(base)?,
+        None => Timetable::new(),
+    };
+    let file = fs::File::open(form)
+        .map_err(|e| ScheduleError::InvalidInput { source: form.display().to_string(), reason: e.to_string() })?;
+    let mut mapping = FormMapping::new(&columns.name_column, &columns.surname_column);
+    mapping.worked_hours_column = columns.worked_hours_column.clone();
+    mapping.tot_hours_column = columns.tot_hours_column.clone();
+    let import = forms::import(file, &mapping, &timetable)?;
+    //skipped rows do not stop the others
+    for error in import.errors.iter() {
+        eprintln!("WARNING: {}", error);
+    }
+    let imported = import.people.len();
+    for person in import.people.into_iter() {
+        timetable.add_person(person);
+    }
+    loader::save(&TimetableFile::from_timetable(&timetable), input)?;
+    println!("{} people imported, written {}", imported, input.display());
+    Ok(true)
+}
+
+fn render(timetable: &Timetable, output: &OutputArgs) -> Result<(), ScheduleError> {
+    let dir = output.output.clone().unwrap_or_else(|| PathBuf::from("."));
+    match output.format {
+        Format::Text => write(output, &terminal::render(timetable, output.output.is_none() && terminal::use_color())?),
+        Format::Json => write(output, &format!("{}\n", json::to_string(&json::output(timetable)?)?)),
+        Format::Html => write(output, &html::report(timetable)?),
+        Format::Csv => print_paths(spreadsheet::write_csv(timetable, &dir)?),
+        Format::Ics => print_paths(ical::write_calendars(timetable, &dir)?),
+        Format::Xlsx => {
+            let path = output.output.clone().unwrap_or_else(|| PathBuf::from("timetable.xlsx"));
+            print_paths(vec![spreadsheet::write_xlsx(timetable, &path)?])
+        }
+    }
+}
+
+//to the output file or, without one, to stdout
+fn write(output: &OutputArgs, content: &str) -> Result<(), ScheduleError> {
+    match &output.output {
+        Some(path) => fs::write(path, content)
+            .map_err(|e| ScheduleError::Output { path: path.display().to_string(), reason: e.to_string() }),
+        None => {
+            print!("{}", content);
+            Ok(())
+        }
+    }
+}
+
+fn print_paths(paths: Vec<PathBuf>) -> Result<(), ScheduleError> {
+    for path in paths.iter() {
+        println!("written {}", path.display());
+    }
+    Ok(())
 }
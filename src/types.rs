@@ -1,3 +1,12 @@
 pub mod person;
 pub mod timetable;
-pub mod scheduler;
\ No newline at end of file
+pub mod scheduler;
+pub mod result;
+pub mod error;
+pub mod diagnosis;
+pub mod constraint;
+pub mod objective;
+pub mod calendar;
+pub mod loader;
+pub mod forms;
+pub mod export;
@@ -0,0 +1,107 @@
+use chrono::{Datelike, Days, NaiveDate};
+
+///a day the desk stays closed
+#[derive(Clone, Debug)]
+pub enum Closure {
+    Date(NaiveDate),
+    ///every year on (month, day), e.g. the patron saint's day
+    Yearly(u32, u32),
+}
+
+impl Closure {
+    pub fn matches(&self, date: NaiveDate) -> bool {
+        match self {
+            Self::Date(d) => *d == date,
+            Self::Yearly(month, day) => date.month() == *month && date.day() == *day,
+        }
+    }
+}
+
+///Easter Sunday of the Gregorian calendar (anonymous algorithm)
+pub fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).unwrap()
+}
+
+///Italian national holidays of a year
+pub fn italian_holidays(year: i32) -> Vec<(NaiveDate, &'static str)> {
+    let date = |month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    let easter = easter_sunday(year);
+    let mut holidays = vec![
+        (date(1, 1), "Capodanno"),
+        (date(1, 6), "Epifania"),
+        (easter, "Pasqua"),
+        (easter.checked_add_days(Days::new(1)).unwrap(), "Lunedì dell'Angelo"),
+        (date(4, 25), "Festa della Liberazione"),
+        (date(5, 1), "Festa del Lavoro"),
+        (date(6, 2), "Festa della Repubblica"),
+        (date(8, 15), "Ferragosto"),
+        (date(11, 1), "Ognissanti"),
+        (date(12, 8), "Immacolata Concezione"),
+        (date(12, 25), "Natale"),
+        (date(12, 26), "Santo Stefano"),
+    ];
+    //national holiday again since 2026
+    if year >= 2026 {
+        holidays.push((date(10, 4), "San Francesco d'Assisi"));
+    }
+    holidays
+}
+
+///name of the national holiday falling on date, if any
+pub fn italian_holiday(date: NaiveDate) -> Option<&'static str> {
+    italian_holidays(date.year()).into_iter()
+        .find(|(d, _)| *d == date)
+        .map(|(_, name)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn easter_known_dates() {
+        assert_eq!(easter_sunday(2024), date(2024, 3, 31));
+        assert_eq!(easter_sunday(2025), date(2025, 4, 20));
+        assert_eq!(easter_sunday(2026), date(2026, 4, 5));
+        assert_eq!(easter_sunday(2027), date(2027, 3, 28));
+    }
+
+    #[test]
+    fn easter_monday_is_a_holiday() {
+        assert_eq!(italian_holiday(date(2026, 4, 6)), Some("Lunedì dell'Angelo"));
+        assert_eq!(italian_holiday(date(2026, 4, 7)), None);
+    }
+
+    #[test]
+    fn san_francesco_since_2026() {
+        assert_eq!(italian_holiday(date(2025, 10, 4)), None);
+        assert_eq!(italian_holiday(date(2026, 10, 4)), Some("San Francesco d'Assisi"));
+        assert_eq!(italian_holidays(2025).len() + 1, italian_holidays(2026).len());
+    }
+
+    #[test]
+    fn closures_match() {
+        assert!(Closure::Yearly(1, 20).matches(date(2027, 1, 20)));
+        assert!(!Closure::Yearly(1, 20).matches(date(2027, 1, 21)));
+        assert!(Closure::Date(date(2026, 5, 4)).matches(date(2026, 5, 4)));
+        assert!(!Closure::Date(date(2026, 5, 4)).matches(date(2027, 5, 4)));
+    }
+}
@@ -0,0 +1,146 @@
+use good_lp::{Constraint, Expression};
+use crate::types::scheduler::Scheduler;
+
+///a group of linear constraints added to the model.
+///Implement it to add site-specific rules; the scheduler gives read access
+///to the variables grid, the people and the turn hours.
+pub trait ScheduleConstraint {
+    ///name of the group, used when reporting conflicts
+    fn name(&self) -> &'static str;
+    fn constraints(&self, scheduler: &Scheduler) -> Vec<Constraint>;
+}
+
+///between the min and max headcount of the shift on every shift
+#[derive(Copy, Clone)]
+pub struct ShiftCoverage;
+
+impl ScheduleConstraint for ShiftCoverage {
+    fn name(&self) -> &'static str {
+        "ShiftCoverage"
+    }
+    fn constraints(&self, scheduler: &Scheduler) -> Vec<Constraint> {
+        let mut c1 = vec![];
+        for w in 0..scheduler.num_weeks() {
+            for d in 0..scheduler.num_days() {
+                for s in 0..scheduler.num_shifts() {
+                    let mut e = Expression::default();
+                    for i in 0..scheduler.num_workers() {
+                        e += scheduler.variable(i, w, d, s); //it is just an index to a variable of the problem so we are ok
+                    }
+                    let coverage = scheduler.coverage(w, d, s);
+                    if coverage.min == coverage.max {
+                        c1.push(e.eq(coverage.min as f64));
+                    } else {
+                        c1.push(e.clone().geq(coverage.min as f64));
+                        c1.push(e.leq(coverage.max as f64));
+                    }
+                }
+            }
+        }
+        c1
+    }
+}
+
+///weekly hours of every person between min and max, in every week of the horizon.
+///The bounds set on a Person take precedence over these
+#[derive(Copy, Clone)]
+pub struct MinMaxWeekHoursPerPerson(pub f64, pub f64);
+
+impl ScheduleConstraint for MinMaxWeekHoursPerPerson {
+    fn name(&self) -> &'static str {
+        "MinMaxWeekHoursPerPerson"
+    }
+    fn constraints(&self, scheduler: &Scheduler) -> Vec<Constraint> {
+        let MinMaxWeekHoursPerPerson(min, max) = *self;
+        let mut c2 = vec![];
+        for i in 0..scheduler.num_workers() {
+            let (min, max) = scheduler.people()[i].week_hours_bounds(min, max);
+            //who completed their contract, or is about to, is left out, see RemainingHoursCap
+            let min = scheduler.reachable_week_min(i, min);
+            for w in 0..scheduler.num_weeks() {
+                let e = scheduler.week_hours(i, w);
+                //nobody can work in a week closed all along, e.g. by local closures
+                let open = (0..scheduler.num_days())
+                    .any(|d| (0..scheduler.num_shifts()).any(|s| scheduler.hours(w, d, s) > 0.0));
+                if open {
+                    c2.push(e.clone().geq(min));
+                }
+                c2.push(e.leq(max));
+            }
+        }
+        c2
+    }
+}
+
+///hours of the horizon within the remaining contract hours, plus at most max_overrun.
+///People who completed their contract get no shift at all
+#[derive(Copy, Clone)]
+pub struct RemainingHoursCap {
+    pub max_overrun: f64,
+}
+
+impl ScheduleConstraint for RemainingHoursCap {
+    fn name(&self) -> &'static str {
+        "RemainingHoursCap"
+    }
+    fn constraints(&self, scheduler: &Scheduler) -> Vec<Constraint> {
+        let mut c = vec![];
+        for i in 0..scheduler.num_workers() {
+            let rem = scheduler.remaining_hours(i);
+            let cap = if rem > 0.0 { rem + self.max_overrun.max(0.0) } else { 0.0 };
+            c.push(scheduler.horizon_hours(i).leq(cap));
+        }
+        c
+    }
+}
+
+///at most one shift per day for every person
+#[derive(Copy, Clone)]
+pub struct NoConsecutiveShifts;
+
+impl ScheduleConstraint for NoConsecutiveShifts {
+    fn name(&self) -> &'static str {
+        "NoConsecutiveShifts"
+    }
+    fn constraints(&self, scheduler: &Scheduler) -> Vec<Constraint> {
+        let mut c3 = vec![];
+        for i in 0..scheduler.num_workers() {
+            for w in 0..scheduler.num_weeks() {
+                for d in 0..scheduler.num_days() {
+                    let mut e = Expression::default();
+                    for s in 0..scheduler.num_shifts() {
+                        e += scheduler.variable(i, w, d, s);
+                    }
+                    c3.push(e.leq(1));
+                }
+            }
+        }
+        c3
+    }
+}
+
+///nobody works a shift they declared to be unavailable for
+#[derive(Copy, Clone)]
+pub struct RespectUnavailability;
+
+impl ScheduleConstraint for RespectUnavailability {
+    fn name(&self) -> &'static str {
+        "RespectUnavailability"
+    }
+    fn constraints(&self, scheduler: &Scheduler) -> Vec<Constraint> {
+        //force to zero every shift a person declared unavailable
+        let mut c4 = vec![];
+        for i in 0..scheduler.num_workers() {
+            for w in 0..scheduler.num_weeks() {
+                for d in 0..scheduler.num_days() {
+                    for s in 0..scheduler.num_shifts() {
+                        if scheduler.is_unavailable(i, d, s) {
+                            c4.push(Expression::from(scheduler.variable(i, w, d, s)).eq(0));
+                        }
+                    }
+                }
+            }
+        }
+        c4
+    }
+}
@@ -0,0 +1,223 @@
+use std::fmt::{Display, Formatter};
+use chrono::NaiveDate;
+use crate::types::person::Person;
+use crate::types::timetable::{Coverage, TimetableConfig};
+
+///a problem in the input that prevents a good (or any) timetable
+#[derive(Clone, Debug)]
+pub enum Issue {
+    ///fewer people than needed expressed a preference for this shift
+    UncoveredShift { week: usize, day: usize, turn: usize, label: String, volunteers: usize, needed: usize },
+    ///fewer people than needed are available for this shift
+    UnstaffableShift { week: usize, day: usize, turn: usize, label: String, available: usize, needed: usize },
+    ///the preferred shifts of a person in a week sum to less than the weekly minimum
+    UnreachableMinHours { person: usize, week: usize, name: String, preferred_hours: f64, min_hours: f64 },
+    ///the weekly maximum of everybody is not enough to cover all the shifts of a week
+    CapacityShortfall { week: usize, shift_hours: f64, max_staff_hours: f64 },
+    ///the weekly minimum of everybody exceeds the hours the shifts of a week provide
+    ExcessMinHours { week: usize, shift_hours: f64, min_staff_hours: f64 },
+    ///the hours left to a person cannot fit the weekly minimum, which is not enforced for them
+    WaivedMinHours { person: usize, name: String, remaining_hours: f64, min_hours: f64 },
+    ///the shifts left before the contract deadline cannot fit the remaining hours
+    UnreachableDeadline { person: usize, name: String, deadline: NaiveDate, remaining_hours: f64, capacity_hours: f64 },
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UncoveredShift { label, volunteers, needed, .. } =>
+                write!(f, "{} needs {} people but only {} prefer it", label, needed, volunteers),
+            Self::UnstaffableShift { label, available, needed, .. } =>
+                write!(f, "{} needs {} people but only {} are available", label, needed, available),
+            Self::UnreachableMinHours { name, week, preferred_hours, min_hours, .. } =>
+                write!(f, "{} prefers only {}h in week {} but must work at least {}h per week",
+                       name, preferred_hours, week + 1, min_hours),
+            Self::CapacityShortfall { week, shift_hours, max_staff_hours } =>
+                write!(f, "shifts of week {} need {}h but the staff can work at most {}h per week",
+                       week + 1, shift_hours, max_staff_hours),
+            Self::ExcessMinHours { week, shift_hours, min_staff_hours } =>
+                write!(f, "the staff must work at least {}h but shifts of week {} provide only {}h",
+                       min_staff_hours, week + 1, shift_hours),
+            Self::WaivedMinHours { name, remaining_hours, min_hours, .. } =>
+                write!(f, "{} has only {}h left, not enough for a shift or for {}h every week: no weekly minimum",
+                       name, remaining_hours, min_hours),
+            Self::UnreachableDeadline { name, deadline, remaining_hours, capacity_hours, .. } =>
+                write!(f, "{} has {}h left but can work at most {}h before {}",
+                       name, remaining_hours, capacity_hours, deadline),
+        }
+    }
+}
+
+///how a person stands against their contract deadline, counted from the first planned week
+#[derive(Clone, Debug)]
+pub struct DeadlineStatus {
+    pub person: usize,
+    pub deadline: NaiveDate,
+    pub remaining_hours: f64,
+    ///hours the person can still work: open shifts they are available for, within the weekly maximum
+    pub capacity_hours: f64,
+    ///weeks with at least an open day before the deadline
+    pub weeks_left: usize,
+}
+
+impl DeadlineStatus {
+    pub fn is_reachable(&self) -> bool {
+        self.remaining_hours <= self.capacity_hours
+    }
+    ///hours per week needed to finish on time
+    pub fn pace(&self) -> f64 {
+        if self.remaining_hours <= 0.0 {
+            return 0.0;
+        }
+        self.remaining_hours / self.weeks_left.max(1) as f64
+    }
+}
+
+///outcome of the analysis of a timetable that could not be scheduled
+#[derive(Clone, Debug, Default)]
+pub struct Diagnosis {
+    ///problems found in the input before solving
+    pub issues: Vec<Issue>,
+    ///constraint groups that cannot be satisfied together
+    pub conflicts: Vec<&'static str>,
+}
+
+impl Display for Diagnosis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.conflicts.is_empty() {
+            write!(f, "conflicting constraints: {}", self.conflicts.join(", "))?;
+        }
+        for issue in self.issues.iter() {
+            write!(f, "\n - {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+///weekly minimum a person can meet with `cap` hours left in the horizon, given the turn hours
+///and coverage ([week][day][turn]) and the shifts the person cannot work ([day][turn]).
+///0 when the hours left do not fit the shortest open shift or the minimum of every open week
+pub fn reachable_week_min(min: f64, cap: f64, hours: &[Vec<Vec<f64>>], coverage: &[Vec<Vec<Coverage>>],
+                          unavailable: &[Vec<bool>]) -> f64 {
+    let mut shortest = f64::INFINITY;
+    let mut open_weeks = 0;
+    for (w, week) in hours.iter().enumerate() {
+        let mut open = false;
+        for (d, day_hours) in week.iter().enumerate() {
+            for (s, h) in day_hours.iter().enumerate() {
+                if *h > 0.0 && coverage[w][d][s].max > 0 {
+                    open = true;
+                    if !unavailable[d][s] {
+                        shortest = shortest.min(*h);
+                    }
+                }
+            }
+        }
+        if open {
+            open_weeks += 1;
+        }
+    }
+    if cap < shortest || cap < min * open_weeks as f64 {
+        return 0.0;
+    }
+    min
+}
+
+///pre-solve analysis over preference weights and unavailability ([person][day][turn]),
+///shift labels, turn hours and coverage ([week][day][turn])
+pub fn analyze(people: &[Person], preferences: &[Vec<Vec<f64>>], unavailable: &[Vec<Vec<bool>>],
+               labels: &[Vec<Vec<String>>], hours: &[Vec<Vec<f64>>], coverage: &[Vec<Vec<Coverage>>],
+               config: &TimetableConfig) -> Vec<Issue> {
+    let mut issues = vec![];
+
+    for (w, week) in hours.iter().enumerate() {
+        for (d, day_hours) in week.iter().enumerate() {
+            for s in 0..day_hours.len() {
+                let needed = coverage[w][d][s].min;
+                let available = unavailable.iter().filter(|u| !u[d][s]).count();
+                let volunteers = preferences.iter().filter(|p| p[d][s] > 0.0).count();
+                let label = labels[w][d][s].clone();
+                if available < needed {
+                    issues.push(Issue::UnstaffableShift { week: w, day: d, turn: s, label, available, needed });
+                } else if volunteers < needed {
+                    issues.push(Issue::UncoveredShift { week: w, day: d, turn: s, label, volunteers, needed });
+                }
+            }
+        }
+    }
+
+    //weekly minimum each person can meet, None for who completed their contract and works no more
+    let mut min_hours = vec![];
+    for (i, person) in people.iter().enumerate() {
+        let rem = person.tot_hours - person.worked_hours;
+        if rem <= 0.0 {
+            min_hours.push(None);
+            continue;
+        }
+        let (min, _) = person.week_hours_bounds(config.min_hours_per_week, config.max_hours_per_week);
+        let reachable = reachable_week_min(min, rem + config.max_overrun_hours.max(0.0), hours, coverage, &unavailable[i]);
+        if reachable < min {
+            issues.push(Issue::WaivedMinHours {
+                person: i,
+                name: format!("{} {}", person.name, person.surname),
+                remaining_hours: rem,
+                min_hours: min,
+            });
+        }
+        min_hours.push(Some(reachable));
+    }
+
+    for (w, week) in hours.iter().enumerate() {
+        //no minimum applies to a week closed all along
+        let open = week.iter().flatten().any(|h| *h > 0.0);
+        for (i, person) in people.iter().enumerate() {
+            let Some(min_hours) = min_hours[i] else {
+                continue;
+            };
+            let mut preferred_hours = 0.0;
+            for (d, day_hours) in week.iter().enumerate() {
+                for (s, h) in day_hours.iter().enumerate() {
+                    if preferences[i][d][s] > 0.0 {
+                        preferred_hours += h;
+                    }
+                }
+            }
+            if open && preferred_hours < min_hours {
+                issues.push(Issue::UnreachableMinHours {
+                    person: i,
+                    week: w,
+                    name: format!("{} {}", person.name, person.surname),
+                    preferred_hours,
+                    min_hours,
+                });
+            }
+        }
+
+        //hours the shifts need at least and can take at most
+        let (mut needed_hours, mut offered_hours) = (0.0, 0.0);
+        for (d, day_hours) in week.iter().enumerate() {
+            for (s, h) in day_hours.iter().enumerate() {
+                needed_hours += h * coverage[w][d][s].min as f64;
+                offered_hours += h * coverage[w][d][s].max as f64;
+            }
+        }
+        let (mut min_staff_hours, mut max_staff_hours) = (0.0, 0.0);
+        for (person, min) in people.iter().zip(min_hours.iter()) {
+            let Some(min) = min else {
+                continue;
+            };
+            let (_, max) = person.week_hours_bounds(config.min_hours_per_week, config.max_hours_per_week);
+            if open {
+                min_staff_hours += min;
+            }
+            max_staff_hours += max;
+        }
+        if needed_hours > max_staff_hours {
+            issues.push(Issue::CapacityShortfall { week: w, shift_hours: needed_hours, max_staff_hours });
+        }
+        if min_staff_hours > offered_hours {
+            issues.push(Issue::ExcessMinHours { week: w, shift_hours: offered_hours, min_staff_hours });
+        }
+    }
+    issues
+}
@@ -0,0 +1,61 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use good_lp::ResolutionError;
+use crate::types::diagnosis::Diagnosis;
+use crate::types::timetable::{Coverage, Day};
+
+#[derive(Debug)]
+pub enum ScheduleError {
+    ///the constraints cannot be satisfied all together
+    Infeasible(Diagnosis),
+    Unbounded,
+    ///any other failure reported by the solver
+    Solver(String),
+    ///person at `index` in Timetable::people has unusable data
+    InvalidPerson { index: usize, reason: String },
+    ///headcount of a turn outside the timetable or with min above max
+    InvalidCoverage { day: Day, turn: usize, coverage: Coverage },
+    ///the ISO week does not exist
+    InvalidWeek { year: i32, week: u32 },
+    ///the scheduler was sized differently from the timetable it was given
+    DimensionMismatch { what: &'static str, expected: usize, found: usize },
+    ///an input file could not be read or does not follow the format
+    InvalidInput { source: String, reason: String },
+    ///an output file could not be written
+    Output { path: String, reason: String },
+    ///the operation needs something the timetable does not have yet
+    Missing(&'static str),
+}
+
+impl Display for ScheduleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Infeasible(diagnosis) =>
+                write!(f, "no timetable satisfies all the enabled constraints. {}", diagnosis),
+            Self::Unbounded => write!(f, "the objective function is unbounded"),
+            Self::Solver(e) => write!(f, "solver error: {}", e),
+            Self::InvalidPerson { index, reason } => write!(f, "invalid person #{}: {}", index, reason),
+            Self::InvalidCoverage { day, turn, coverage } =>
+                write!(f, "invalid coverage {}..{} for {} turn #{}", coverage.min, coverage.max, day, turn),
+            Self::InvalidWeek { year, week } => write!(f, "invalid ISO week {}-W{:02}", year, week),
+            Self::DimensionMismatch { what, expected, found } =>
+                write!(f, "mismatched number of {}: expected {}, found {}", what, expected, found),
+            Self::InvalidInput { source, reason } => write!(f, "invalid input {}: {}", source, reason),
+            Self::Output { path, reason } => write!(f, "cannot write {}: {}", path, reason),
+            Self::Missing(what) => write!(f, "missing {}", what),
+        }
+    }
+}
+
+impl Error for ScheduleError {}
+
+impl From<ResolutionError> for ScheduleError {
+    fn from(e: ResolutionError) -> Self {
+        match e {
+            ResolutionError::Infeasible => Self::Infeasible(Diagnosis::default()),
+            ResolutionError::Unbounded => Self::Unbounded,
+            ResolutionError::Other(s) => Self::Solver(s.to_string()),
+            ResolutionError::Str(s) => Self::Solver(s),
+        }
+    }
+}
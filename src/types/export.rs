@@ -0,0 +1,75 @@
+pub mod ical;
+pub mod html;
+pub mod terminal;
+pub mod spreadsheet;
+pub mod json;
+
+use std::fs;
+use std::path::PathBuf;
+use crate::types::error::ScheduleError;
+use crate::types::result::ScheduleStats;
+use crate::types::timetable::Timetable;
+
+//people assigned to each [week][day][turn]
+type Assignments = [Vec<Vec<Vec<usize>>>];
+
+//computed assignments and stats, or the error telling to run calc first
+fn computed(timetable: &Timetable) -> Result<(&Assignments, &ScheduleStats), ScheduleError> {
+    match (timetable.computed(), timetable.stats()) {
+        (Some(computed), Some(stats)) => Ok((computed, stats)),
+        _ => Err(ScheduleError::Missing("computed timetable, run calc first")),
+    }
+}
+
+fn write_file(path: PathBuf, content: String) -> Result<PathBuf, ScheduleError> {
+    fs::write(&path, content)
+        .map_err(|e| ScheduleError::Output { path: path.display().to_string(), reason: e.to_string() })?;
+    Ok(path)
+}
+
+//share of the assigned hours of each person that falls on a preferred shift, 1 with no hours
+fn preferred_share(timetable: &Timetable, computed: &Assignments, preferences: &[Vec<Vec<f64>>]) -> Vec<f64> {
+    let hours = timetable.get_turn_hours();
+    (0..timetable.people.len()).map(|i| {
+        let (mut preferred, mut assigned) = (0.0, 0.0);
+        for (w, week) in computed.iter().enumerate() {
+            for (d, turns) in week.iter().enumerate() {
+                for (s, people) in turns.iter().enumerate() {
+                    if people.contains(&i) {
+                        assigned += hours[w][d][s];
+                        if preferences[i][d][s] > 0.0 {
+                            preferred += hours[w][d][s];
+                        }
+                    }
+                }
+            }
+        }
+        if assigned > 0.0 { preferred / assigned } else { 1.0 }
+    }).collect()
+}
+
+//Andrea on the Monday morning, a preferred shift, and Luca on the Friday afternoon,
+//an avoided one, of the first week of a Monday to Friday timetable
+#[cfg(test)]
+fn solved(mut timetable: Timetable) -> Timetable {
+    use crate::types::person::{Person, Preference};
+    use crate::types::result::{PersonStats, ScheduleResult};
+    use crate::types::timetable::{Day, Turn};
+
+    let mut andrea = Person::new("Andrea", "Bonvissuto");
+    andrea.preferences.push(Preference::new(Day::Mon, Turn::MORNING));
+    let mut luca = Person::new("Luca", "De Candia");
+    luca.preferences.push(Preference::avoid(Day::Fri, Turn::AFTERNOON));
+    timetable.add_person(andrea);
+    timetable.add_person(luca);
+    let mut assignments = vec![vec![vec![vec![]; timetable.turns().len()]; timetable.days().len()]];
+    assignments[0][0][0].push(0);
+    assignments[0][4][1].push(1);
+    let stats = ScheduleStats {
+        people: vec![PersonStats { weekly_hours: vec![4.0], hours: 4.0, remaining_hours: 146.0 },
+                     PersonStats { weekly_hours: vec![5.0], hours: 5.0, remaining_hours: 145.0 }],
+        objective: -9.0,
+    };
+    timetable.set_result(ScheduleResult { assignments, stats });
+    timetable
+}
@@ -0,0 +1,144 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use chrono::Days;
+use crate::types::error::ScheduleError;
+use crate::types::export::{computed, preferred_share, write_file};
+use crate::types::timetable::Timetable;
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 1em; }
+table { border-collapse: collapse; margin-bottom: 1.5em; }
+th, td { border: 1px solid #444; padding: 0.3em 0.6em; vertical-align: top; }
+th { background: #eee; }
+td.closed { background: #ddd; color: #666; font-style: italic; }
+.preferred { color: #1a7f37; font-weight: bold; }
+.avoided { color: #b35900; }
+.num { text-align: right; }
+.legend span { margin-right: 1.5em; }
+@media print { body { margin: 0; } h2 { page-break-before: auto; } table { page-break-inside: avoid; } }
+";
+
+///printable page with a days x turns grid per week and the hours of every person
+pub fn report(timetable: &Timetable) -> Result<String, ScheduleError> {
+    let (computed, stats) = computed(timetable)?;
+    let (preferences, _) = timetable.get_people_preferences_and_rem_hours()?;
+    let mut html = String::new();
+    //writing to a String never fails
+    let _ = write!(html, "<!DOCTYPE html>\n<html lang=\"it\">\n<head>\n<meta charset=\"utf-8\">\n\
+                          <title>Orario borsisti</title>\n<style>{}</style>\n</head>\n<body>\n", STYLE);
+
+    //GRID
+    for (w, week) in computed.iter().enumerate() {
+        //from the Monday, which is there also when no day is open
+        let monday = timetable.week_start().and_then(|start| start.checked_add_days(Days::new(7 * w as u64)));
+        let title = match monday {
+            Some(date) => format!("Week of {}", date),
+            None => format!("Week {}", w + 1),
+        };
+        let _ = write!(html, "<h2>{}</h2>\n<table>\n<tr><th></th>", escape(&title));
+        for d in 0..week.len() {
+            let _ = write!(html, "<th>{}</th>", escape(&timetable.day_label(w, d)));
+        }
+        html.push_str("</tr>\n");
+        for (s, turn) in timetable.turns().iter().enumerate() {
+            let _ = write!(html, "<tr><th>{}<br>{}-{}</th>", escape(&turn.name), turn.start, turn.end);
+            for (d, turns) in week.iter().enumerate() {
+                if let Some(name) = timetable.closure_of(w, d) {
+                    let _ = write!(html, "<td class=\"closed\">{}</td>", escape(&name));
+                    continue;
+                }
+                let names: Vec<String> = turns[s].iter().map(|i| {
+                    let person = &timetable.people[*i];
+                    let class = match preferences[*i][d][s] {
+                        weight if weight > 0.0 => "preferred",
+                        weight if weight < 0.0 => "avoided",
+                        _ => "assigned",
+                    };
+                    format!("<span class=\"{}\">{} {}</span>", class, escape(&person.name), escape(&person.surname))
+                }).collect();
+                let _ = write!(html, "<td>{}</td>", names.join("<br>"));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n");
+    }
+    html.push_str("<p class=\"legend\"><span class=\"preferred\">preferred shift</span>\
+                   <span class=\"assigned\">no preference</span>\
+                   <span class=\"avoided\">asked not to work it</span></p>\n");
+
+    //HOURS PER PERSON
+    html.push_str("<h2>Hours</h2>\n<table>\n<tr><th>Person</th>");
+    for w in 0..computed.len() {
+        let _ = write!(html, "<th>W{}</th>", w + 1);
+    }
+    html.push_str("<th>Total</th><th>Remaining</th><th>Preferred</th></tr>\n");
+    let share = preferred_share(timetable, computed, &preferences);
+    for (i, person) in timetable.people.iter().enumerate() {
+        let _ = write!(html, "<tr><td>{} {}</td>", escape(&person.name), escape(&person.surname));
+        for h in stats.people[i].weekly_hours.iter() {
+            let _ = write!(html, "<td class=\"num\">{}h</td>", h);
+        }
+        let _ = writeln!(html, "<td class=\"num\">{}h</td><td class=\"num\">{}h</td><td class=\"num\">{:.0}%</td></tr>",
+                         stats.people[i].hours, stats.people[i].remaining_hours, share[i] * 100.0);
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    Ok(html)
+}
+
+pub fn write_report(timetable: &Timetable, path: &Path) -> Result<PathBuf, ScheduleError> {
+    write_file(path.to_path_buf(), report(timetable)?)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::export::solved;
+    use crate::types::result::{ScheduleResult, ScheduleStats};
+    use crate::types::timetable::Day;
+
+    #[test]
+    fn grid_and_hours() {
+        let html = report(&solved(Timetable::for_week(2026, 16).unwrap())).unwrap();
+        assert!(html.contains("<h2>Week of 2026-04-13</h2>"));
+        assert!(html.contains("<th>FRI 2026-04-17</th>"));
+        assert!(html.contains("<span class=\"preferred\">Andrea Bonvissuto</span>"));
+        assert!(html.contains("<span class=\"avoided\">Luca De Candia</span>"));
+        assert!(html.contains("<tr><td>Andrea Bonvissuto</td><td class=\"num\">4h</td><td class=\"num\">4h</td>\
+                               <td class=\"num\">146h</td><td class=\"num\">100%</td></tr>"));
+        assert!(html.contains("<td class=\"num\">0%</td>"));
+        assert!(html.ends_with("</html>\n"));
+    }
+
+    #[test]
+    fn holidays_are_closed_cells() {
+        //Easter Monday
+        let html = report(&solved(Timetable::for_week(2026, 15).unwrap())).unwrap();
+        assert_eq!(html.matches("<td class=\"closed\">Lunedì dell'Angelo</td>").count(), 2);
+        assert!(!html.contains("Andrea Bonvissuto</span>"));
+    }
+
+    #[test]
+    fn weeks_without_open_days() {
+        let mut timetable = Timetable::for_week(2026, 16).unwrap();
+        for day in Day::ALL {
+            timetable.close_day(day);
+        }
+        timetable.set_result(ScheduleResult { assignments: vec![vec![]], stats: ScheduleStats { people: vec![], objective: 0.0 } });
+        let html = report(&timetable).unwrap();
+        assert!(html.contains("<h2>Week of 2026-04-13</h2>"));
+    }
+
+    #[test]
+    fn escapes_text() {
+        assert_eq!(escape("<b>\"Rossi & Bianchi\"</b>"), "&lt;b&gt;&quot;Rossi &amp; Bianchi&quot;&lt;/b&gt;");
+    }
+
+    #[test]
+    fn needs_a_computed_timetable() {
+        assert!(matches!(report(&Timetable::new()), Err(ScheduleError::Missing(_))));
+    }
+}
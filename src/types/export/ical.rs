@@ -0,0 +1,152 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::{NaiveDate, Utc};
+use crate::types::error::ScheduleError;
+use crate::types::export::{computed, write_file};
+use crate::types::timetable::{ClockTime, Timetable};
+
+///calendar with the shifts of a person, one VEVENT per assigned shift
+pub fn person_calendar(timetable: &Timetable, person: usize) -> Result<String, ScheduleError> {
+    calendar_of(timetable, &[person])
+}
+
+///calendar with the shifts of everybody, the person's name in each summary
+pub fn calendar(timetable: &Timetable) -> Result<String, ScheduleError> {
+    let people: Vec<usize> = (0..timetable.people.len()).collect();
+    calendar_of(timetable, &people)
+}
+
+///writes Surname_Name.ics for every person and timetable.ics with all the shifts into dir,
+///returning the paths written
+pub fn write_calendars(timetable: &Timetable, dir: &Path) -> Result<Vec<PathBuf>, ScheduleError> {
+    fs::create_dir_all(dir)
+        .map_err(|e| ScheduleError::Output { path: dir.display().to_string(), reason: e.to_string() })?;
+    let mut paths = vec![];
+    for (i, person) in timetable.people.iter().enumerate() {
+        let name = format!("{}_{}.ics", person.surname, person.name).replace(|c: char| !c.is_alphanumeric() && c != '.', "_");
+        paths.push(write_file(dir.join(name), person_calendar(timetable, i)?)?);
+    }
+    paths.push(write_file(dir.join("timetable.ics"), calendar(timetable)?)?);
+    Ok(paths)
+}
+
+fn calendar_of(timetable: &Timetable, people: &[usize]) -> Result<String, ScheduleError> {
+    let (computed, _) = computed(timetable)?;
+    if timetable.week_start().is_none() {
+        return Err(ScheduleError::Missing("dates of the shifts, schedule a concrete ISO week"));
+    }
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//orario_borsisti//timetable//IT".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for (w, week) in computed.iter().enumerate() {
+        for (d, turns) in week.iter().enumerate() {
+            let Some(date) = timetable.date_of(w, d) else {
+                continue;
+            };
+            for (s, assigned) in turns.iter().enumerate() {
+                let turn = &timetable.turns()[s];
+                for i in assigned.iter().filter(|i| people.contains(i)) {
+                    let person = &timetable.people[*i];
+                    let summary = if people.len() == 1 {
+                        format!("Desk shift ({})", turn.name)
+                    } else {
+                        format!("{} {} ({})", person.name, person.surname, turn.name)
+                    };
+                    lines.push("BEGIN:VEVENT".to_string());
+                    lines.push(format!("UID:{}-{}-{}@orario_borsisti", date.format("%Y%m%d"), s, i));
+                    lines.push(format!("DTSTAMP:{}", stamp));
+                    //floating local times: calendars show them in the device time zone
+                    lines.push(format!("DTSTART:{}", date_time(date, turn.start)));
+                    lines.push(format!("DTEND:{}", date_time(date, timetable.turn_end(d, s))));
+                    lines.push(format!("SUMMARY:{}", escape(&summary)));
+                    lines.push("END:VEVENT".to_string());
+                }
+            }
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+    //RFC 5545 wants CRLF and lines folded at 75 octets
+    Ok(lines.iter().map(|l| fold(l)).collect::<Vec<_>>().join("\r\n") + "\r\n")
+}
+
+fn date_time(date: NaiveDate, time: ClockTime) -> String {
+    format!("{}T{:02}{:02}00", date.format("%Y%m%d"), time.hour, time.minute)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace('\n', "\\n")
+}
+
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            len = 1;
+        }
+        folded.push(c);
+        len += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::export::solved;
+
+    #[test]
+    fn short_lines_are_not_folded() {
+        assert_eq!(fold("SUMMARY:Desk shift (morning)"), "SUMMARY:Desk shift (morning)");
+    }
+
+    #[test]
+    fn folds_at_75_octets() {
+        let line = format!("SUMMARY:{}", "a".repeat(150));
+        let folded = fold(&line);
+        let parts: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].len(), 75);
+        assert!(parts[1..].iter().all(|p| p.starts_with(' ') && p.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn folds_multibyte_names_on_char_boundaries() {
+        //2 octets each, the 75th octet falls in the middle of a character
+        let line = format!("SUMMARY:{}", "è".repeat(60));
+        let folded = fold(&line);
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= 75, "{} octets", part.len());
+        }
+        assert_eq!(folded.split("\r\n").next().unwrap().len(), 74);
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn escapes_text() {
+        assert_eq!(escape("De Rossi, Daniele; a\\b\nc"), r"De Rossi\, Daniele\; a\\b\nc");
+    }
+
+    #[test]
+    fn events_of_a_concrete_week() {
+        let timetable = solved(Timetable::for_week(2026, 16).unwrap());
+        let calendar = person_calendar(&timetable, 0).unwrap();
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 1);
+        assert!(calendar.contains("DTSTART:20260413T083000\r\n"));
+        assert!(calendar.contains("DTEND:20260413T123000\r\n"));
+    }
+
+    #[test]
+    fn abstract_weeks_have_no_dates() {
+        let timetable = solved(Timetable::new());
+        assert!(matches!(calendar(&timetable), Err(ScheduleError::Missing(_))));
+    }
+}
@@ -0,0 +1,283 @@
+use serde::{Deserialize, Serialize};
+use crate::types::error::ScheduleError;
+use crate::types::export::computed;
+use crate::types::loader::TimetableFile;
+use crate::types::result::{PersonStats, ScheduleResult, ScheduleStats};
+use crate::types::timetable::Timetable;
+
+///version of the output schema, bumped on any incompatible change (see the README)
+pub const SCHEMA_VERSION: u32 = 1;
+
+///outcome of a run, as written by `--format json`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ScheduleOutput {
+    pub schema_version: u32,
+    ///"solved", "infeasible", "unbounded" or "error"
+    pub status: String,
+    ///why the run failed, when it did
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub objective: Option<f64>,
+    ///names of the constraint groups enabled
+    pub constraints: Vec<String>,
+    pub objective_terms: Vec<ObjectiveTermOutput>,
+    ///the input in the JSON input format, enough to run again
+    pub input: TimetableFile,
+    ///empty when the run failed
+    pub weeks: Vec<WeekOutput>,
+    pub people: Vec<PersonOutput>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ObjectiveTermOutput {
+    pub name: String,
+    pub weight: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WeekOutput {
+    ///1 is the first week of the horizon
+    pub week: usize,
+    pub days: Vec<DayOutput>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DayOutput {
+    ///MON ... SUN
+    pub day: String,
+    ///YYYY-MM-DD, when scheduling concrete weeks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    ///name of the holiday or closure, when closed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub closed: Option<String>,
+    pub shifts: Vec<ShiftOutput>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ShiftOutput {
+    pub turn: String,
+    ///HH:MM
+    pub start: String,
+    pub end: String,
+    pub hours: f64,
+    ///indexes in the top level people
+    pub people: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PersonOutput {
+    pub index: usize,
+    pub name: String,
+    pub surname: String,
+    pub acronym: String,
+    pub tot_hours: f64,
+    pub worked_hours: f64,
+    ///hours assigned in each week, empty when the run failed
+    pub weekly_hours: Vec<f64>,
+    pub hours: f64,
+    ///contract hours left after the horizon
+    pub remaining_hours: f64,
+}
+
+///output of a computed timetable
+pub fn output(timetable: &Timetable) -> Result<ScheduleOutput, ScheduleError> {
+    let (computed, stats) = computed(timetable)?;
+    let hours = timetable.get_turn_hours();
+    let mut out = header(timetable, "solved", None);
+    out.objective = Some(stats.objective);
+    out.weeks = computed.iter().enumerate().map(|(w, week)| WeekOutput {
+        week: w + 1,
+        days: week.iter().enumerate().map(|(d, turns)| DayOutput {
+            day: timetable.days()[d].name().to_string(),
+            date: timetable.date_of(w, d).map(|date| date.to_string()),
+            closed: timetable.closure_of(w, d),
+            shifts: turns.iter().enumerate().map(|(s, people)| ShiftOutput {
+                turn: timetable.turns()[s].name.clone(),
+                start: timetable.turns()[s].start.to_string(),
+                end: timetable.turn_end(d, s).to_string(),
+                hours: hours[w][d][s],
+                people: people.clone(),
+            }).collect(),
+        }).collect(),
+    }).collect();
+    for (p, s) in out.people.iter_mut().zip(stats.people.iter()) {
+        p.weekly_hours = s.weekly_hours.clone();
+        p.hours = s.hours;
+        p.remaining_hours = s.remaining_hours;
+    }
+    Ok(out)
+}
+
+///output of a run that failed, with the input echoed and no assignments
+pub fn error_output(timetable: &Timetable, error: &ScheduleError) -> ScheduleOutput {
+    let status = match error {
+        ScheduleError::Infeasible(_) => "infeasible",
+        ScheduleError::Unbounded => "unbounded",
+        _ => "error",
+    };
+    header(timetable, status, Some(error.to_string()))
+}
+
+///a solved output of the supported schema version
+pub fn parse(content: &str) -> Result<ScheduleOutput, ScheduleError> {
+    let output: ScheduleOutput = serde_json::from_str(content).map_err(|e| invalid(e.to_string()))?;
+    if output.schema_version != SCHEMA_VERSION {
+        return Err(invalid(format!("schema version {} is not supported, expected {}",
+                                   output.schema_version, SCHEMA_VERSION)));
+    }
+    if output.status != "solved" {
+        return Err(invalid(format!("the run was not solved: {}", output.status)));
+    }
+    Ok(output)
+}
+
+///the timetable of a solved output, with its assignments, ready to be rendered again
+pub fn read(content: &str) -> Result<Timetable, ScheduleError> {
+    let output = parse(content)?;
+    let mut timetable = output.input.into_timetable()?;
+    let (days, turns, people) = (timetable.days().len(), timetable.turns().len(), timetable.people.len());
+    let mut assignments = vec![];
+    for week in output.weeks.into_iter() {
+        if week.days.len() != days {
+            return Err(ScheduleError::DimensionMismatch { what: "days", expected: days, found: week.days.len() });
+        }
+        let mut w = vec![];
+        for day in week.days.into_iter() {
+            if day.shifts.len() != turns {
+                return Err(ScheduleError::DimensionMismatch { what: "shifts", expected: turns, found: day.shifts.len() });
+            }
+            if let Some(i) = day.shifts.iter().flat_map(|s| s.people.iter()).find(|i| **i >= people) {
+                return Err(invalid(format!("person #{} is not in the input", i)));
+            }
+            w.push(day.shifts.into_iter().map(|s| s.people).collect());
+        }
+        assignments.push(w);
+    }
+    if assignments.len() != timetable.weeks() {
+        return Err(ScheduleError::DimensionMismatch { what: "weeks", expected: timetable.weeks(), found: assignments.len() });
+    }
+    if output.people.len() != people {
+        return Err(ScheduleError::DimensionMismatch { what: "people", expected: people, found: output.people.len() });
+    }
+    let stats = ScheduleStats {
+        people: output.people.into_iter().map(|p| PersonStats {
+            weekly_hours: p.weekly_hours,
+            hours: p.hours,
+            remaining_hours: p.remaining_hours,
+        }).collect(),
+        objective: output.objective.unwrap_or_default(),
+    };
+    timetable.set_result(ScheduleResult { assignments, stats });
+    Ok(timetable)
+}
+
+pub fn to_string(output: &ScheduleOutput) -> Result<String, ScheduleError> {
+    serde_json::to_string_pretty(output)
+        .map_err(|e| ScheduleError::Output { path: "json".to_string(), reason: e.to_string() })
+}
+
+fn invalid(reason: String) -> ScheduleError {
+    ScheduleError::InvalidInput { source: "json output".to_string(), reason }
+}
+
+fn header(timetable: &Timetable, status: &str, message: Option<String>) -> ScheduleOutput {
+    ScheduleOutput {
+        schema_version: SCHEMA_VERSION,
+        status: status.to_string(),
+        message,
+        objective: None,
+        constraints: timetable.constraint_names().iter().map(|n| n.to_string()).collect(),
+        //none when the objective cannot be built, e.g. in the output of that very error
+        objective_terms: timetable.objective().map(|objective| objective.terms().into_iter()
+            .map(|(name, weight)| ObjectiveTermOutput { name: name.to_string(), weight })
+            .collect()).unwrap_or_default(),
+        input: TimetableFile::from_timetable(timetable),
+        weeks: vec![],
+        people: timetable.people.iter().enumerate().map(|(index, p)| PersonOutput {
+            index,
+            name: p.name.clone(),
+            surname: p.surname.clone(),
+            acronym: p.acronym(),
+            tot_hours: p.tot_hours,
+            worked_hours: p.worked_hours,
+            weekly_hours: vec![],
+            hours: 0.0,
+            remaining_hours: p.tot_hours - p.worked_hours,
+        }).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use super::*;
+    use crate::types::export;
+
+    fn solved() -> Timetable {
+        export::solved(Timetable::for_week(2026, 16).unwrap())
+    }
+
+    fn document() -> Value {
+        serde_json::from_str(&to_string(&output(&solved()).unwrap()).unwrap()).unwrap()
+    }
+
+    fn read_value(value: &Value) -> Result<Timetable, ScheduleError> {
+        read(&value.to_string())
+    }
+
+    #[test]
+    fn round_trip() {
+        let doc = document();
+        assert_eq!(doc["schema_version"], 1);
+        assert_eq!(doc["status"], "solved");
+        assert_eq!(doc["weeks"][0]["days"][4]["shifts"][1]["end"], "18:00");
+        let timetable = read_value(&doc).unwrap();
+        assert_eq!(timetable.week_start(), solved().week_start());
+        assert_eq!(timetable.computed(), solved().computed());
+        let stats = timetable.stats().unwrap();
+        assert_eq!(stats.objective, -9.0);
+        assert_eq!(stats.people[1].remaining_hours, 145.0);
+    }
+
+    #[test]
+    fn rejects_other_schema_versions() {
+        let mut doc = document();
+        doc["schema_version"] = Value::from(SCHEMA_VERSION + 1);
+        assert!(matches!(read_value(&doc), Err(ScheduleError::InvalidInput { .. })));
+        assert!(matches!(parse(&doc.to_string()), Err(ScheduleError::InvalidInput { .. })));
+    }
+
+    #[test]
+    fn rejects_failed_runs() {
+        let timetable = solved();
+        let failed = error_output(&timetable, &ScheduleError::Unbounded);
+        assert_eq!(failed.status, "unbounded");
+        assert!(failed.weeks.is_empty());
+        assert!(matches!(read(&to_string(&failed).unwrap()), Err(ScheduleError::InvalidInput { .. })));
+    }
+
+    #[test]
+    fn checks_dimensions() {
+        let mut days = document();
+        days["weeks"][0]["days"].as_array_mut().unwrap().pop();
+        assert!(matches!(read_value(&days), Err(ScheduleError::DimensionMismatch { what: "days", expected: 5, found: 4 })));
+
+        let mut shifts = document();
+        shifts["weeks"][0]["days"][2]["shifts"].as_array_mut().unwrap().pop();
+        assert!(matches!(read_value(&shifts), Err(ScheduleError::DimensionMismatch { what: "shifts", .. })));
+
+        let mut weeks = document();
+        weeks["input"]["weeks"] = Value::from(2);
+        assert!(matches!(read_value(&weeks), Err(ScheduleError::DimensionMismatch { what: "weeks", expected: 2, found: 1 })));
+
+        let mut people = document();
+        people["people"].as_array_mut().unwrap().pop();
+        assert!(matches!(read_value(&people), Err(ScheduleError::DimensionMismatch { what: "people", .. })));
+
+        let mut index = document();
+        index["weeks"][0]["days"][0]["shifts"][0]["people"] = Value::from(vec![7]);
+        assert!(matches!(read_value(&index), Err(ScheduleError::InvalidInput { .. })));
+    }
+}
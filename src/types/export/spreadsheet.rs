@@ -0,0 +1,176 @@
+use std::path::{Path, PathBuf};
+use rust_xlsxwriter::{Format, Workbook};
+use crate::types::error::ScheduleError;
+use crate::types::export::{computed, write_file};
+use crate::types::timetable::Timetable;
+
+enum Cell {
+    Text(String),
+    Number(f64),
+}
+
+impl Cell {
+    fn text(&self) -> String {
+        match self {
+            Self::Text(t) => t.clone(),
+            Self::Number(n) => n.to_string(),
+        }
+    }
+}
+
+//a view of the schedule: column titles and rows
+struct Sheet {
+    name: &'static str,
+    titles: Vec<String>,
+    rows: Vec<Vec<Cell>>,
+}
+
+//one row per person per assigned shift
+fn shifts(timetable: &Timetable) -> Result<Sheet, ScheduleError> {
+    let (computed, _) = computed(timetable)?;
+    let (preferences, _) = timetable.get_people_preferences_and_rem_hours()?;
+    let hours = timetable.get_turn_hours();
+    let titles = ["week", "date", "day", "turn", "start", "end", "hours", "name", "surname", "preferred"];
+    let mut rows = vec![];
+    for (w, week) in computed.iter().enumerate() {
+        for (d, turns) in week.iter().enumerate() {
+            let date = timetable.date_of(w, d).map(|d| d.to_string()).unwrap_or_default();
+            for (s, people) in turns.iter().enumerate() {
+                let turn = &timetable.turns()[s];
+                for i in people.iter() {
+                    let person = &timetable.people[*i];
+                    rows.push(vec![
+                        Cell::Number((w + 1) as f64),
+                        Cell::Text(date.clone()),
+                        Cell::Text(timetable.days()[d].name().to_string()),
+                        Cell::Text(turn.name.clone()),
+                        Cell::Text(turn.start.to_string()),
+                        Cell::Text(timetable.turn_end(d, s).to_string()),
+                        Cell::Number(hours[w][d][s]),
+                        Cell::Text(person.name.clone()),
+                        Cell::Text(person.surname.clone()),
+                        Cell::Text(if preferences[*i][d][s] > 0.0 { "yes" } else { "no" }.to_string()),
+                    ]);
+                }
+            }
+        }
+    }
+    Ok(Sheet { name: "Shifts", titles: titles.iter().map(|t| t.to_string()).collect(), rows })
+}
+
+//one row per person: contract, hours of each week and remaining
+fn hours(timetable: &Timetable) -> Result<Sheet, ScheduleError> {
+    let (computed, stats) = computed(timetable)?;
+    let mut titles: Vec<String> = ["name", "surname", "tot_hours", "worked_hours"].iter().map(|t| t.to_string()).collect();
+    titles.extend((0..computed.len()).map(|w| format!("week_{}", w + 1)));
+    titles.push("hours".to_string());
+    titles.push("remaining_hours".to_string());
+    let rows = timetable.people.iter().zip(stats.people.iter()).map(|(person, s)| {
+        let mut row = vec![
+            Cell::Text(person.name.clone()),
+            Cell::Text(person.surname.clone()),
+            Cell::Number(person.tot_hours),
+            Cell::Number(person.worked_hours),
+        ];
+        row.extend(s.weekly_hours.iter().map(|h| Cell::Number(*h)));
+        row.push(Cell::Number(s.hours));
+        row.push(Cell::Number(s.remaining_hours));
+        row
+    }).collect();
+    Ok(Sheet { name: "Hours", titles, rows })
+}
+
+fn to_csv(sheet: &Sheet) -> Result<String, ScheduleError> {
+    let failed = |reason: String| ScheduleError::Output { path: sheet.name.to_string(), reason };
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(&sheet.titles).map_err(|e| failed(e.to_string()))?;
+    for row in sheet.rows.iter() {
+        writer.write_record(row.iter().map(Cell::text)).map_err(|e| failed(e.to_string()))?;
+    }
+    let bytes = writer.into_inner().map_err(|e| failed(e.to_string()))?;
+    String::from_utf8(bytes).map_err(|e| failed(e.to_string()))
+}
+
+///assigned shifts as CSV, one row per person per shift
+pub fn shifts_csv(timetable: &Timetable) -> Result<String, ScheduleError> {
+    to_csv(&shifts(timetable)?)
+}
+
+///hours ledger as CSV, one row per person
+pub fn hours_csv(timetable: &Timetable) -> Result<String, ScheduleError> {
+    to_csv(&hours(timetable)?)
+}
+
+///writes shifts.csv and hours.csv into dir, returning the paths written
+pub fn write_csv(timetable: &Timetable, dir: &Path) -> Result<Vec<PathBuf>, ScheduleError> {
+    Ok(vec![
+        write_file(dir.join("shifts.csv"), shifts_csv(timetable)?)?,
+        write_file(dir.join("hours.csv"), hours_csv(timetable)?)?,
+    ])
+}
+
+///workbook with a Shifts and an Hours sheet
+pub fn write_xlsx(timetable: &Timetable, path: &Path) -> Result<PathBuf, ScheduleError> {
+    let failed = |e: rust_xlsxwriter::XlsxError| ScheduleError::Output { path: path.display().to_string(), reason: e.to_string() };
+    let bold = Format::new().set_bold();
+    let mut workbook = Workbook::new();
+    for sheet in [shifts(timetable)?, hours(timetable)?] {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(sheet.name).map_err(failed)?;
+        for (c, title) in sheet.titles.iter().enumerate() {
+            worksheet.write_string_with_format(0, c as u16, title, &bold).map_err(failed)?;
+        }
+        for (r, row) in sheet.rows.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                let (r, c) = (r as u32 + 1, c as u16);
+                match cell {
+                    Cell::Text(t) => worksheet.write_string(r, c, t).map_err(failed)?,
+                    Cell::Number(n) => worksheet.write_number(r, c, *n).map_err(failed)?,
+                };
+            }
+        }
+        worksheet.autofit();
+    }
+    workbook.save(path).map_err(failed)?;
+    Ok(path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::export::solved;
+
+    #[test]
+    fn shifts_of_a_concrete_week() {
+        let csv = shifts_csv(&solved(Timetable::for_week(2026, 16).unwrap())).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines, vec![
+            "week,date,day,turn,start,end,hours,name,surname,preferred",
+            "1,2026-04-13,MON,morning,08:30,12:30,4,Andrea,Bonvissuto,yes",
+            "1,2026-04-17,FRI,afternoon,13:00,18:00,5,Luca,De Candia,no",
+        ]);
+    }
+
+    #[test]
+    fn abstract_weeks_have_no_dates() {
+        let csv = shifts_csv(&solved(Timetable::new())).unwrap();
+        assert_eq!(csv.lines().nth(1), Some("1,,MON,morning,08:30,12:30,4,Andrea,Bonvissuto,yes"));
+    }
+
+    #[test]
+    fn hours_ledger() {
+        let csv = hours_csv(&solved(Timetable::for_week(2026, 16).unwrap())).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines, vec![
+            "name,surname,tot_hours,worked_hours,week_1,hours,remaining_hours",
+            "Andrea,Bonvissuto,150,0,4,4,146",
+            "Luca,De Candia,150,0,5,5,145",
+        ]);
+    }
+
+    #[test]
+    fn needs_a_computed_timetable() {
+        assert!(matches!(shifts_csv(&Timetable::new()), Err(ScheduleError::Missing(_))));
+        assert!(matches!(hours_csv(&Timetable::new()), Err(ScheduleError::Missing(_))));
+    }
+}
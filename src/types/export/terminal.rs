@@ -0,0 +1,168 @@
+use std::env;
+use std::fmt::Write;
+use std::io::{stdout, IsTerminal};
+use crate::types::error::ScheduleError;
+use crate::types::export::{computed, preferred_share};
+use crate::types::timetable::Timetable;
+
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+///colors only on an interactive terminal, unless NO_COLOR is set
+pub fn use_color() -> bool {
+    stdout().is_terminal() && env::var_os("NO_COLOR").is_none()
+}
+
+pub fn print(timetable: &Timetable) -> Result<(), ScheduleError> {
+    print!("{}", render(timetable, use_color())?);
+    Ok(())
+}
+
+///days x turns grid of acronyms for every week followed by the hours of every person.
+///Preferred shifts are green (or marked with * without colors), avoided ones yellow (or !)
+pub fn render(timetable: &Timetable, color: bool) -> Result<String, ScheduleError> {
+    let (computed, stats) = computed(timetable)?;
+    let (preferences, _) = timetable.get_people_preferences_and_rem_hours()?;
+    let paint = |code: &str, text: &str| if color { format!("{}{}{}", code, text, RESET) } else { text.to_string() };
+    let mut out = String::new();
+
+    //GRID
+    for (w, week) in computed.iter().enumerate() {
+        //cells as (visible text, painted text)
+        let mut header = vec![(String::new(), String::new())];
+        for d in 0..week.len() {
+            let label = timetable.day_label(w, d);
+            header.push((label.clone(), paint(BOLD, &label)));
+        }
+        let mut rows = vec![header];
+        for (s, turn) in timetable.turns().iter().enumerate() {
+            let mut row = vec![(turn.name.clone(), paint(BOLD, &turn.name))];
+            for (d, turns) in week.iter().enumerate() {
+                if timetable.closure_of(w, d).is_some() {
+                    row.push(("closed".to_string(), paint(DIM, "closed")));
+                    continue;
+                }
+                let (mut plain, mut painted) = (vec![], vec![]);
+                for i in turns[s].iter() {
+                    let acronym = timetable.people[*i].acronym();
+                    let (mark, code) = match preferences[*i][d][s] {
+                        weight if weight > 0.0 => ("*", GREEN),
+                        weight if weight < 0.0 => ("!", YELLOW),
+                        _ => ("", ""),
+                    };
+                    if color {
+                        plain.push(acronym.clone());
+                        painted.push(if code.is_empty() { acronym } else { paint(code, &acronym) });
+                    } else {
+                        plain.push(format!("{}{}", acronym, mark));
+                        painted.push(format!("{}{}", acronym, mark));
+                    }
+                }
+                row.push((plain.join(" "), painted.join(" ")));
+            }
+            rows.push(row);
+        }
+        out.push_str(&table(&rows));
+        for d in 0..week.len() {
+            if let Some(name) = timetable.closure_of(w, d) {
+                let _ = writeln!(out, "{} closed: {}", timetable.day_label(w, d), name);
+            }
+        }
+        out.push('\n');
+    }
+    let legend = if color {
+        format!("{} {}\n\n", paint(GREEN, "preferred"), paint(YELLOW, "avoided"))
+    } else {
+        "* preferred  ! avoided\n\n".to_string()
+    };
+    out.push_str(&legend);
+
+    //SUMMARY
+    let share = preferred_share(timetable, computed, &preferences);
+    let titles = ["person", "hours", "remaining", "satisfaction"];
+    let mut rows = vec![titles.iter().map(|t| (t.to_string(), paint(BOLD, t))).collect::<Vec<_>>()];
+    for (i, person) in timetable.people.iter().enumerate() {
+        let cells = [format!("{} {}", person.name, person.surname),
+                     format!("{}h", stats.people[i].hours),
+                     format!("{}h", stats.people[i].remaining_hours),
+                     format!("{:.0}%", share[i] * 100.0)];
+        rows.push(cells.into_iter().map(|c| (c.clone(), c)).collect());
+    }
+    out.push_str(&table(&rows));
+    let _ = writeln!(out, "objective: {}", stats.objective);
+    Ok(out)
+}
+
+//rows of (visible text, painted text) cells, columns padded on the visible width
+fn table(rows: &[Vec<(String, String)>]) -> String {
+    let columns = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| rows.iter().filter_map(|r| r.get(c)).map(|(plain, _)| plain.chars().count()).max().unwrap_or(0))
+        .collect();
+    let separator: String = widths.iter().map(|w| format!("+{}", "-".repeat(w + 2))).collect::<String>() + "+\n";
+    let mut out = separator.clone();
+    for (r, row) in rows.iter().enumerate() {
+        for (c, (plain, painted)) in row.iter().enumerate() {
+            let _ = write!(out, "| {}{} ", painted, " ".repeat(widths[c] - plain.chars().count()));
+        }
+        out.push_str("|\n");
+        //under the header
+        if r == 0 {
+            out.push_str(&separator);
+        }
+    }
+    out.push_str(&separator);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::export::solved;
+
+    //trimmed cells of the first table line containing text
+    fn cells(out: &str, text: &str) -> Vec<String> {
+        let line = out.lines().find(|l| l.contains(text)).unwrap();
+        line.split('|').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect()
+    }
+
+    #[test]
+    fn marks_without_colors() {
+        let out = render(&solved(Timetable::for_week(2026, 16).unwrap()), false).unwrap();
+        assert!(!out.contains('\x1b'));
+        assert_eq!(cells(&out, "MON 2026-04-13")[0], "MON 2026-04-13");
+        assert_eq!(cells(&out, "morning"), vec!["morning", "AnBo*"]);
+        assert_eq!(cells(&out, "afternoon"), vec!["afternoon", "LuDe!"]);
+        assert!(out.contains("* preferred  ! avoided\n"));
+    }
+
+    #[test]
+    fn summary_rows() {
+        let out = render(&solved(Timetable::for_week(2026, 16).unwrap()), false).unwrap();
+        assert_eq!(cells(&out, "satisfaction"), vec!["person", "hours", "remaining", "satisfaction"]);
+        assert_eq!(cells(&out, "Andrea Bonvissuto"), vec!["Andrea Bonvissuto", "4h", "146h", "100%"]);
+        assert_eq!(cells(&out, "Luca De Candia"), vec!["Luca De Candia", "5h", "145h", "0%"]);
+        assert!(out.ends_with("objective: -9\n"));
+    }
+
+    #[test]
+    fn closed_cells() {
+        //Easter Monday
+        let out = render(&solved(Timetable::for_week(2026, 15).unwrap()), false).unwrap();
+        assert_eq!(cells(&out, "morning")[1], "closed");
+        assert_eq!(cells(&out, "afternoon")[1], "closed");
+        assert!(out.contains("MON 2026-04-06 closed: Lunedì dell'Angelo\n"));
+        assert!(!out.contains("AnBo"));
+    }
+
+    #[test]
+    fn colors_replace_the_marks() {
+        let out = render(&solved(Timetable::for_week(2026, 16).unwrap()), true).unwrap();
+        assert!(out.contains(&format!("{}AnBo{}", GREEN, RESET)));
+        assert!(out.contains(&format!("{}LuDe{}", YELLOW, RESET)));
+        assert!(!out.contains("AnBo*"));
+    }
+}
@@ -0,0 +1,263 @@
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use crate::types::error::ScheduleError;
+use crate::types::person::{Person, Preference};
+use crate::types::timetable::{Day, Timetable, Turn};
+
+///how the columns of a form export map to people and shifts.
+///A shift column holds a yes/no answer; a day column (e.g. a checkbox grid row
+///"Disponibilità [Lunedì]") holds the comma separated names of the turns chosen that day
+#[derive(Clone, Debug)]
+pub struct FormMapping {
+    pub name_column: String,
+    pub surname_column: String,
+    pub worked_hours_column: Option<String>,
+    pub tot_hours_column: Option<String>,
+    ///column header => shift it answers for
+    pub shifts: Vec<(String, Day, Turn)>,
+    ///column header => day whose turns it lists
+    pub days: Vec<(String, Day)>,
+    ///extra names of the turns, e.g. "mattina"
+    pub turn_aliases: Vec<(String, Turn)>,
+    ///answers that mean yes, compared in lowercase
+    pub truthy: Vec<String>,
+}
+
+impl Default for FormMapping {
+    fn default() -> Self {
+        Self {
+            name_column: "Nome".to_string(),
+            surname_column: "Cognome".to_string(),
+            worked_hours_column: None,
+            tot_hours_column: None,
+            shifts: vec![],
+            days: vec![],
+            turn_aliases: vec![("mattina".to_string(), Turn::MORNING),
+                               ("mattino".to_string(), Turn::MORNING),
+                               ("pomeriggio".to_string(), Turn::AFTERNOON),
+            ],
+            truthy: ["sì", "si", "x", "yes", "y", "true", "1", "✓"].iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+impl FormMapping {
+    pub fn new(name_column: &str, surname_column: &str) -> Self {
+        Self {
+            name_column: name_column.to_string(),
+            surname_column: surname_column.to_string(),
+            ..Default::default()
+        }
+    }
+    pub fn add_shift(&mut self, column: &str, day: Day, turn: Turn) -> &mut Self {
+        self.shifts.push((column.to_string(), day, turn));
+        self
+    }
+    pub fn add_day(&mut self, column: &str, day: Day) -> &mut Self {
+        self.days.push((column.to_string(), day));
+        self
+    }
+    pub fn add_turn_alias(&mut self, alias: &str, turn: Turn) -> &mut Self {
+        self.turn_aliases.push((alias.to_lowercase(), turn));
+        self
+    }
+    pub fn set_truthy(&mut self, truthy: &[&str]) -> &mut Self {
+        self.truthy = truthy.iter().map(|s| s.to_lowercase()).collect();
+        self
+    }
+    ///maps every header naming a day (and possibly a turn) that is not mapped yet,
+    ///e.g. "Lunedì mattina" or "Disponibilità [Lunedì]"
+    pub fn detect(&mut self, headers: &[&str], timetable: &Timetable) -> &mut Self {
+        for header in headers.iter() {
+            let mapped = self.shifts.iter().any(|(c, _, _)| c == header)
+                || self.days.iter().any(|(c, _)| c == header);
+            if mapped {
+                continue;
+            }
+            let words: Vec<&str> = header.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+            let Some(day) = words.iter().find_map(|w| Day::parse(w)) else {
+                continue;
+            };
+            match words.iter().find_map(|w| self.turn(w, timetable)) {
+                Some(turn) => self.add_shift(header, day, turn),
+                None => self.add_day(header, day),
+            };
+        }
+        self
+    }
+    ///turn named by an answer or a header word, by timetable name or alias
+    pub fn turn(&self, name: &str, timetable: &Timetable) -> Option<Turn> {
+        let name = name.trim().to_lowercase();
+        timetable.turn_by_name(&name)
+            .or_else(|| self.turn_aliases.iter().find(|(a, _)| *a == name).map(|(_, t)| *t))
+    }
+    pub fn is_truthy(&self, answer: &str) -> bool {
+        let answer = answer.trim().to_lowercase();
+        self.truthy.contains(&answer)
+    }
+}
+
+///a row of the export that could not be turned into a person
+#[derive(Clone, Debug)]
+pub struct RowError {
+    ///line in the file, the header is line 1
+    pub line: usize,
+    pub reason: String,
+}
+
+impl Display for RowError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+///people read from a form export and the rows that were skipped
+#[derive(Clone, Default)]
+pub struct FormImport {
+    pub people: Vec<Person>,
+    pub errors: Vec<RowError>,
+}
+
+///reads a form export, one row per person. Headers not in the mapping are detected
+///when they name a day, the others (timestamp, email...) are ignored.
+///Fails only when the file is not a CSV or misses a column of the mapping
+pub fn import(reader: impl Read, mapping: &FormMapping, timetable: &Timetable) -> Result<FormImport, ScheduleError> {
+    let mut csv = csv::ReaderBuilder::new().trim(csv::Trim::All).flexible(true).from_reader(reader);
+    let headers = csv.headers().map_err(|e| invalid(e.to_string()))?.clone();
+    let mut mapping = mapping.clone();
+    mapping.detect(&headers.iter().collect::<Vec<_>>(), timetable);
+
+    let column = |name: &str, what: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name.trim()))
+        .ok_or(invalid(format!("missing {} column \"{}\"", what, name)));
+    let name_col = column(&mapping.name_column, "name")?;
+    let surname_col = column(&mapping.surname_column, "surname")?;
+    let worked_col = mapping.worked_hours_column.as_deref().map(|c| column(c, "worked hours")).transpose()?;
+    let tot_col = mapping.tot_hours_column.as_deref().map(|c| column(c, "total hours")).transpose()?;
+    let shifts: Vec<(usize, Day, Turn)> = mapping.shifts.iter()
+        .map(|(c, d, t)| Ok((column(c, "shift")?, *d, *t)))
+        .collect::<Result<_, ScheduleError>>()?;
+    let days: Vec<(usize, Day)> = mapping.days.iter()
+        .map(|(c, d)| Ok((column(c, "day")?, *d)))
+        .collect::<Result<_, ScheduleError>>()?;
+
+    let mut result = FormImport::default();
+    for (row, record) in csv.records().enumerate() {
+        let line = row + 2;
+        let person = record.map_err(|e| e.to_string()).and_then(|record| {
+            let field = |col: usize| record.get(col).unwrap_or_default();
+            let hours = |col: Option<usize>, default: f64| -> Result<f64, String> {
+                match col.map(field).filter(|v| !v.is_empty()) {
+                    None => Ok(default),
+                    //spreadsheets in Italian use the decimal comma
+                    Some(v) => v.replace(',', ".").parse::<f64>()
+                        .map_err(|_| format!("\"{}\" is not a number of hours", v)),
+                }
+            };
+            let mut person = Person::new(field(name_col), field(surname_col));
+            person.worked_hours = hours(worked_col, person.worked_hours)?;
+            person.tot_hours = hours(tot_col, person.tot_hours)?;
+            for (col, day, turn) in shifts.iter() {
+                if mapping.is_truthy(field(*col)) {
+                    person.preferences.push(Preference::new(*day, *turn));
+                }
+            }
+            for (col, day) in days.iter() {
+                for answer in field(*col).split([',', ';']).map(str::trim).filter(|a| !a.is_empty()) {
+                    let turn = mapping.turn(answer, timetable)
+                        .ok_or(format!("unknown turn \"{}\" in \"{}\"", answer, &headers[*col]))?;
+                    person.preferences.push(Preference::new(*day, turn));
+                }
+            }
+            person.validate()?;
+            Ok(person)
+        });
+        match person {
+            Ok(person) => result.people.push(person),
+            Err(reason) => result.errors.push(RowError { line, reason }),
+        }
+    }
+    Ok(result)
+}
+
+fn invalid(reason: String) -> ScheduleError {
+    ScheduleError::InvalidInput { source: "form".to_string(), reason }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = "Informazioni cronologiche,Nome,Cognome,Ore svolte,Lunedì mattina,Lunedì pomeriggio,Disponibilità [Martedì]\n\
+                          2026/04/01 10:00,Andrea,Bonvissuto,\"41,5\",Sì,x,\"Mattina, Pomeriggio\"\n\
+                          2026/04/01 10:05,Luca,De Candia,36,no,,Sera\n\
+                          2026/04/01 10:07,Daniele,De Rossi,,,si,\n";
+
+    fn mapping() -> FormMapping {
+        FormMapping {
+            worked_hours_column: Some("Ore svolte".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn detects_italian_headers() {
+        let timetable = Timetable::new();
+        let mut mapping = FormMapping::default();
+        mapping.detect(&["Informazioni cronologiche", "Nome", "Lunedì mattina", "Venerdì Pomeriggio", "Disponibilità [Martedì]"], &timetable);
+        let shifts: Vec<(&str, Day, Turn)> = mapping.shifts.iter().map(|(c, d, t)| (c.as_str(), *d, *t)).collect();
+        assert_eq!(shifts, vec![("Lunedì mattina", Day::Mon, Turn::MORNING), ("Venerdì Pomeriggio", Day::Fri, Turn::AFTERNOON)]);
+        assert_eq!(mapping.days, vec![("Disponibilità [Martedì]".to_string(), Day::Tue)]);
+    }
+
+    #[test]
+    fn truthy_answers() {
+        let mapping = FormMapping::default();
+        for answer in ["sì", "Sì", " si ", "x", "X", "yes"] {
+            assert!(mapping.is_truthy(answer), "{}", answer);
+        }
+        for answer in ["no", "", "forse"] {
+            assert!(!mapping.is_truthy(answer), "{}", answer);
+        }
+    }
+
+    #[test]
+    fn imports_people_and_skips_bad_rows() {
+        let import = import(EXPORT.as_bytes(), &mapping(), &Timetable::new()).unwrap();
+        assert_eq!(import.people.len(), 2);
+        let andrea = &import.people[0];
+        assert_eq!(andrea.worked_hours, 41.5);
+        let slots: Vec<(Day, Turn)> = andrea.preferences.iter().map(|p| (p.day, p.turn)).collect();
+        assert_eq!(slots, vec![(Day::Mon, Turn::MORNING), (Day::Mon, Turn::AFTERNOON),
+                               (Day::Tue, Turn::MORNING), (Day::Tue, Turn::AFTERNOON)]);
+        let daniele = &import.people[1];
+        assert_eq!((daniele.surname.as_str(), daniele.worked_hours), ("De Rossi", 0.0));
+        assert_eq!(daniele.preferences.len(), 1);
+        //Luca answered an evening turn the timetable does not have
+        assert_eq!(import.errors.len(), 1);
+        assert_eq!(import.errors[0].line, 3);
+        assert!(import.errors[0].reason.contains("Sera"), "{}", import.errors[0]);
+    }
+
+    #[test]
+    fn missing_name_column() {
+        let export = "Timestamp,Name,Cognome\n2026/04/01,Andrea,Bonvissuto\n";
+        assert!(matches!(import(export.as_bytes(), &FormMapping::default(), &Timetable::new()),
+                         Err(ScheduleError::InvalidInput { .. })));
+    }
+
+    #[test]
+    fn missing_mapped_columns() {
+        let missing = |mapping: &FormMapping| match import(EXPORT.as_bytes(), mapping, &Timetable::new()) {
+            Err(ScheduleError::InvalidInput { reason, .. }) => reason,
+            other => panic!("expected an invalid input, found {:?}", other.map(|i| i.people.len())),
+        };
+        let tot = FormMapping { tot_hours_column: Some("Ore totali".to_string()), ..mapping() };
+        assert_eq!(missing(&tot), "missing total hours column \"Ore totali\"");
+        let mut shift = mapping();
+        shift.add_shift("Mercoledì sera", Day::Wed, Turn::AFTERNOON);
+        assert!(missing(&shift).contains("Mercoledì sera"));
+        let mut day = mapping();
+        day.add_day("Disponibilità [Giovedì]", Day::Thu);
+        assert!(missing(&day).contains("Giovedì"));
+    }
+}
@@ -0,0 +1,568 @@
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use crate::types::error::ScheduleError;
+use crate::types::person::{Person, Preference, Unavailability};
+use crate::types::calendar::Closure;
+use crate::types::timetable::{ClockTime, Coverage, Day, Timetable, TimetableConfig, Turn, TurnDef, TurnHours};
+
+///a timetable as described by a JSON or TOML input file, see the README for the format
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TimetableFile {
+    ///first ISO week to schedule, abstract weeks when missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub week: Option<IsoWeek>,
+    ///weeks of the planning horizon, 1 when missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weeks: Option<usize>,
+    #[serde(default)]
+    pub config: TimetableConfig,
+    ///turns of every day, morning and afternoon when missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub turns: Option<Vec<TurnDefRecord>>,
+    ///open days, Monday to Friday when missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days: Option<Vec<DayRecord>>,
+    ///headcount of the turns needing other than one person
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub coverage: Vec<CoverageRecord>,
+    ///local closures on top of the national holidays
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub closures: Vec<ClosureRecord>,
+    pub people: Vec<PersonRecord>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TurnDefRecord {
+    pub name: String,
+    ///HH:MM
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct DayRecord {
+    pub day: String,
+    ///hours of each turn that day, 0 when closed. The full turns when missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hours: Option<Vec<f64>>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CoverageRecord {
+    pub day: String,
+    pub turn: TurnRecord,
+    pub min: usize,
+    ///min when missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<usize>,
+}
+
+///a single date (YYYY-MM-DD) or a month and day of every year
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ClosureRecord {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub month: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct IsoWeek {
+    pub year: i32,
+    pub week: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PersonRecord {
+    pub name: String,
+    pub surname: String,
+    #[serde(default)]
+    pub worked_hours: f64,
+    #[serde(default = "default_tot_hours")]
+    pub tot_hours: f64,
+    ///YYYY-MM-DD
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_week_hours: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_week_hours: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_week_hours: Option<f64>,
+    #[serde(default)]
+    pub preferences: Vec<PreferenceRecord>,
+    #[serde(default)]
+    pub unavailable: Vec<SlotRecord>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PreferenceRecord {
+    pub day: String,
+    pub turn: TurnRecord,
+    ///1 when missing, negative for "prefer not"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+    ///1 is the first choice, used when weight is missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SlotRecord {
+    pub day: String,
+    pub turn: TurnRecord,
+}
+
+///a turn by position (0 is the first turn of the day) or by name
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(untagged)]
+pub enum TurnRecord {
+    Index(usize),
+    Name(String),
+}
+
+fn default_tot_hours() -> f64 {
+    150.0
+}
+
+///reads a timetable from a .json, .toml or .csv file
+pub fn load(path: &Path) -> Result<Timetable, ScheduleError> {
+    let source = path.display().to_string();
+    let content = fs::read_to_string(path)
+        .map_err(|e| ScheduleError::InvalidInput { source: source.clone(), reason: e.to_string() })?;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+    match extension.as_str() {
+        "json" => from_json(&content),
+        "toml" => from_toml(&content),
+        "csv" => from_csv(content.as_bytes()),
+        _ => Err(ScheduleError::InvalidInput {
+            source,
+            reason: "unknown extension, expected .json, .toml or .csv".to_string(),
+        }),
+    }
+}
+
+///writes the file in the format of its extension, .json or .toml
+pub fn save(file: &TimetableFile, path: &Path) -> Result<(), ScheduleError> {
+    let failed = |reason: String| ScheduleError::Output { path: path.display().to_string(), reason };
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+    let content = match extension.as_str() {
+        "json" => serde_json::to_string_pretty(file).map_err(|e| failed(e.to_string()))?,
+        "toml" => toml::to_string_pretty(file).map_err(|e| failed(e.to_string()))?,
+        _ => return Err(failed("unknown extension, expected .json or .toml".to_string())),
+    };
+    fs::write(path, content).map_err(|e| failed(e.to_string()))
+}
+
+pub fn from_json(content: &str) -> Result<Timetable, ScheduleError> {
+    let file: TimetableFile = serde_json::from_str(content)
+        .map_err(|e| invalid("json", e.to_string()))?;
+    file.into_timetable()
+}
+
+pub fn from_toml(content: &str) -> Result<Timetable, ScheduleError> {
+    let file: TimetableFile = toml::from_str(content)
+        .map_err(|e| invalid("toml", e.to_string()))?;
+    file.into_timetable()
+}
+
+///one row per person: name, surname, worked_hours, tot_hours and deadline (optional),
+///then a column per shift named "<day> <turn>" holding a preference mark.
+///Marks: empty for nothing, "x" preferred, a number for a weight, "-" prefer not, "!" unavailable
+pub fn from_csv(reader: impl Read) -> Result<Timetable, ScheduleError> {
+    let mut timetable = Timetable::new();
+    let mut csv = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(reader);
+    let headers = csv.headers().map_err(|e| invalid("csv", e.to_string()))?.clone();
+
+    //shift columns
+    let mut shifts = vec![];
+    for (col, header) in headers.iter().enumerate() {
+        if matches!(header, "name" | "surname" | "worked_hours" | "tot_hours" | "deadline") {
+            continue;
+        }
+        let slot = header.split_once(' ')
+            .and_then(|(day, turn)| Some((Day::parse(day)?, timetable.turn_by_name(turn)?)));
+        match slot {
+            Some((day, turn)) => shifts.push((col, day, turn)),
+            None => return Err(invalid("csv", format!("column \"{}\" is not a shift like \"MON morning\"", header))),
+        }
+    }
+    let column = |name: &str| headers.iter().position(|h| h == name);
+    let (Some(name_col), Some(surname_col)) = (column("name"), column("surname")) else {
+        return Err(invalid("csv", "name and surname columns are required".to_string()));
+    };
+
+    for (row, record) in csv.records().enumerate() {
+        //the header is line 1
+        let line = row + 2;
+        let record = record.map_err(|e| invalid("csv", e.to_string()))?;
+        let field = |col: Option<usize>| col.and_then(|c| record.get(c)).filter(|v| !v.is_empty());
+        let number = |name: &str| -> Result<Option<f64>, ScheduleError> {
+            field(column(name)).map(|v| v.parse::<f64>()
+                .map_err(|_| invalid("csv", format!("line {}: {} \"{}\" is not a number", line, name, v))))
+                .transpose()
+        };
+
+        let mut person = Person::new(field(Some(name_col)).unwrap_or_default(),
+                                     field(Some(surname_col)).unwrap_or_default());
+        person.worked_hours = number("worked_hours")?.unwrap_or(0.0);
+        person.tot_hours = number("tot_hours")?.unwrap_or(person.tot_hours);
+        person.deadline = field(column("deadline")).map(parse_date)
+            .transpose()
+            .map_err(|reason| invalid("csv", format!("line {}: {}", line, reason)))?;
+        for (col, day, turn) in shifts.iter() {
+            match field(Some(*col)) {
+                None => {}
+                Some("x") | Some("X") => person.preferences.push(Preference::new(*day, *turn)),
+                Some("-") => person.preferences.push(Preference::avoid(*day, *turn)),
+                Some("!") => person.unavailable.push(Unavailability::new(*day, *turn)),
+                Some(v) => match v.parse::<f64>() {
+                    Ok(weight) => person.preferences.push(Preference::with_weight(*day, *turn, weight)),
+                    Err(_) => return Err(invalid("csv", format!("line {}: unknown mark \"{}\" for {}", line, v, &headers[*col]))),
+                },
+            }
+        }
+        timetable.add_person(person);
+    }
+    Ok(timetable)
+}
+
+impl TimetableFile {
+    pub fn into_timetable(self) -> Result<Timetable, ScheduleError> {
+        let mut timetable = match &self.turns {
+            Some(turns) => Timetable::with_turns(turns.iter().map(TurnDefRecord::to_turn).collect::<Result<_, _>>()?),
+            None => Timetable::new(),
+        };
+        if let Some(w) = &self.week {
+            timetable.set_week(w.year, w.week)?;
+        }
+        if let Some(weeks) = self.weeks {
+            timetable.set_weeks(weeks);
+        }
+        timetable.set_config(self.config);
+        if let Some(days) = &self.days {
+            if days.is_empty() {
+                return Err(invalid("days", "no open day".to_string()));
+            }
+            for day in Day::ALL {
+                timetable.close_day(day);
+            }
+            for record in days.iter() {
+                let day = Day::parse(&record.day).ok_or_else(|| invalid("days", format!("unknown day \"{}\"", record.day)))?;
+                let hours = match &record.hours {
+                    Some(hours) if hours.len() != timetable.turns().len() => {
+                        return Err(invalid("days", format!("{} has {} hours for {} turns", day, hours.len(), timetable.turns().len())));
+                    }
+                    Some(hours) => hours.iter().map(|h| TurnHours(*h as f32)).collect(),
+                    None => timetable.turns().iter().map(|t| TurnHours(t.hours() as f32)).collect(),
+                };
+                timetable.open_day(day, hours)?;
+            }
+        }
+        for record in self.coverage.iter() {
+            let (day, turn) = resolve_slot(&timetable, &record.day, &record.turn)
+                .map_err(|reason| invalid("coverage", reason))?;
+            timetable.set_coverage(day, turn, Coverage::new(record.min, record.max.unwrap_or(record.min)));
+        }
+        for record in self.closures.iter() {
+            let closure = record.to_closure().map_err(|reason| invalid("closures", reason))?;
+            timetable.add_closure(closure, &record.name);
+        }
+        for (index, record) in self.people.into_iter().enumerate() {
+            let person = record.into_person(&timetable)
+                .map_err(|reason| ScheduleError::InvalidPerson { index, reason })?;
+            timetable.add_person(person);
+        }
+        Ok(timetable)
+    }
+    ///the part of a timetable the file format describes, e.g. to echo the input next to a result
+    pub fn from_timetable(timetable: &Timetable) -> Self {
+        Self {
+            week: timetable.week_start().map(|monday| IsoWeek {
+                year: monday.iso_week().year(),
+                week: monday.iso_week().week(),
+            }),
+            weeks: Some(timetable.weeks()),
+            config: timetable.config,
+            turns: Some(timetable.turns().iter().map(|t| TurnDefRecord {
+                name: t.name.clone(),
+                start: t.start.to_string(),
+                end: t.end.to_string(),
+            }).collect()),
+            days: Some(timetable.days().iter().zip(timetable.turnhours().iter()).map(|(day, hours)| {
+                let hours: Vec<f64> = hours.iter().map(|h| h.hours()).collect();
+                //full turns are left implicit
+                let full = hours.iter().zip(timetable.turns().iter()).all(|(h, t)| *h == t.hours());
+                DayRecord { day: day.name().to_string(), hours: if full { None } else { Some(hours) } }
+            }).collect()),
+            coverage: timetable.custom_coverage().iter().map(|(day, turn, c)| CoverageRecord {
+                day: day.name().to_string(),
+                turn: turn_record(timetable, *turn),
+                min: c.min,
+                max: if c.max == c.min { None } else { Some(c.max) },
+            }).collect(),
+            closures: timetable.closures().iter().map(|(closure, name)| match closure {
+                Closure::Date(date) => ClosureRecord { name: name.clone(), date: Some(date.to_string()), month: None, day: None },
+                Closure::Yearly(month, day) => ClosureRecord { name: name.clone(), date: None, month: Some(*month), day: Some(*day) },
+            }).collect(),
+            people: timetable.people.iter().map(|p| PersonRecord::from_person(p, timetable)).collect(),
+        }
+    }
+    ///turns this input into the one of the following horizon: the hours of the run
+    ///(one per person) are added to worked_hours and the week moves past the horizon
+    pub fn advance(&mut self, hours: &[f64]) -> Result<&mut Self, ScheduleError> {
+        if hours.len() != self.people.len() {
+            return Err(ScheduleError::DimensionMismatch { what: "people", expected: self.people.len(), found: hours.len() });
+        }
+        for (person, h) in self.people.iter_mut().zip(hours.iter()) {
+            person.worked_hours += h;
+        }
+        if let Some(week) = &mut self.week {
+            let next = NaiveDate::from_isoywd_opt(week.year, week.week, Weekday::Mon)
+                .and_then(|monday| monday.checked_add_days(Days::new(7 * self.weeks.unwrap_or(1) as u64)))
+                .ok_or(ScheduleError::InvalidWeek { year: week.year, week: week.week })?;
+            week.year = next.iso_week().year();
+            week.week = next.iso_week().week();
+        }
+        Ok(self)
+    }
+}
+
+impl TurnDefRecord {
+    pub fn to_turn(&self) -> Result<TurnDef, ScheduleError> {
+        let (start, end) = (parse_time(&self.start), parse_time(&self.end));
+        match (start, end) {
+            (Some(start), Some(end)) if start < end => Ok(TurnDef::new(&self.name, start, end)),
+            _ => Err(invalid("turns", format!("{}: expected HH:MM times with start before end, found {} - {}",
+                                              self.name, self.start, self.end))),
+        }
+    }
+}
+
+impl ClosureRecord {
+    pub fn to_closure(&self) -> Result<Closure, String> {
+        match (&self.date, self.month, self.day) {
+            (Some(date), None, None) => Ok(Closure::Date(parse_date(date)?)),
+            //any leap year accepts every valid month and day
+            (None, Some(month), Some(day)) if NaiveDate::from_ymd_opt(2000, month, day).is_some() =>
+                Ok(Closure::Yearly(month, day)),
+            _ => Err(format!("{}: expected a date or a valid month and day", self.name)),
+        }
+    }
+}
+
+impl PersonRecord {
+    pub fn from_person(person: &Person, timetable: &Timetable) -> Self {
+        let turn = |t: Turn| turn_record(timetable, t);
+        Self {
+            name: person.name.clone(),
+            surname: person.surname.clone(),
+            worked_hours: person.worked_hours,
+            tot_hours: person.tot_hours,
+            deadline: person.deadline.map(|d| d.to_string()),
+            min_week_hours: person.min_week_hours,
+            max_week_hours: person.max_week_hours,
+            target_week_hours: person.target_week_hours,
+            preferences: person.preferences.iter().map(|p| PreferenceRecord {
+                day: p.day.name().to_string(),
+                turn: turn(p.turn),
+                weight: Some(p.weight),
+                rank: None,
+            }).collect(),
+            unavailable: person.unavailable.iter().map(|u| SlotRecord {
+                day: u.day.name().to_string(),
+                turn: turn(u.turn),
+            }).collect(),
+        }
+    }
+
+    ///the person with days and turns resolved against the timetable
+    pub fn into_person(self, timetable: &Timetable) -> Result<Person, String> {
+        let mut person = Person::with_all(&self.name, &self.surname, vec![], self.worked_hours, self.tot_hours);
+        person.deadline = self.deadline.as_deref().map(parse_date).transpose()?;
+        person.min_week_hours = self.min_week_hours;
+        person.max_week_hours = self.max_week_hours;
+        person.target_week_hours = self.target_week_hours;
+        for p in self.preferences.iter() {
+            let (day, turn) = resolve_slot(timetable, &p.day, &p.turn)?;
+            let preference = match (p.weight, p.rank) {
+                (Some(weight), _) => Preference::with_weight(day, turn, weight),
+                (None, Some(rank)) => Preference::ranked(day, turn, rank),
+                (None, None) => Preference::new(day, turn),
+            };
+            person.preferences.push(preference);
+        }
+        for u in self.unavailable.iter() {
+            let (day, turn) = resolve_slot(timetable, &u.day, &u.turn)?;
+            person.unavailable.push(Unavailability::new(day, turn));
+        }
+        Ok(person)
+    }
+}
+
+fn resolve_slot(timetable: &Timetable, day: &str, turn: &TurnRecord) -> Result<(Day, Turn), String> {
+    let d = Day::parse(day).ok_or(format!("unknown day \"{}\"", day))?;
+    let t = match turn {
+        TurnRecord::Index(i) => Turn(*i),
+        TurnRecord::Name(name) => timetable.turn_by_name(name).ok_or(format!("unknown turn \"{}\"", name))?,
+    };
+    Ok((d, t))
+}
+
+//by name when the turn exists, by position otherwise
+fn turn_record(timetable: &Timetable, turn: Turn) -> TurnRecord {
+    match timetable.turns().get(turn.0) {
+        Some(def) => TurnRecord::Name(def.name.clone()),
+        None => TurnRecord::Index(turn.0),
+    }
+}
+
+fn parse_time(time: &str) -> Option<ClockTime> {
+    let (hour, minute) = time.trim().split_once(':')?;
+    let (hour, minute) = (hour.parse().ok()?, minute.parse().ok()?);
+    (hour < 24 && minute < 60).then(|| ClockTime::new(hour, minute))
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| format!("invalid date \"{}\", expected YYYY-MM-DD", date))
+}
+
+fn invalid(source: &str, reason: String) -> ScheduleError {
+    ScheduleError::InvalidInput { source: source.to_string(), reason }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "name,surname,worked_hours,tot_hours,MON morning,MON afternoon,TUE morning,TUE afternoon\n\
+                       Andrea,Bonvissuto,41,150,x,-,!,2.5\n";
+
+    #[test]
+    fn csv_marks() {
+        let timetable = from_csv(CSV.as_bytes()).unwrap();
+        let person = &timetable.people[0];
+        assert_eq!((person.name.as_str(), person.worked_hours, person.tot_hours), ("Andrea", 41.0, 150.0));
+        let weights: Vec<(Day, usize, f64)> = person.preferences.iter().map(|p| (p.day, p.turn.0, p.weight)).collect();
+        assert_eq!(weights, vec![(Day::Mon, 0, 1.0), (Day::Mon, 1, -1.0), (Day::Tue, 1, 2.5)]);
+        assert_eq!(person.unavailable.len(), 1);
+        assert_eq!((person.unavailable[0].day, person.unavailable[0].turn), (Day::Tue, Turn::MORNING));
+    }
+
+    #[test]
+    fn csv_bad_mark_reports_its_line() {
+        let content = format!("{}Luca,De Candia,36,150,,,?,\n", CSV);
+        match from_csv(content.as_bytes()) {
+            Err(ScheduleError::InvalidInput { reason, .. }) => {
+                assert!(reason.starts_with("line 3:"), "{}", reason);
+                assert!(reason.contains("\"?\""), "{}", reason);
+            }
+            other => panic!("expected an invalid input, found {:?}", other.map(|t| t.people.len())),
+        }
+    }
+
+    #[test]
+    fn csv_unknown_shift_column() {
+        let content = "name,surname,MON evening\nAndrea,Bonvissuto,x\n";
+        assert!(matches!(from_csv(content.as_bytes()), Err(ScheduleError::InvalidInput { .. })));
+    }
+
+    #[test]
+    fn toml_into_timetable() {
+        let content = r#"
+            week = { year = 2026, week = 4 }
+            turns = [
+                { name = "morning", start = "08:30", end = "12:30" },
+                { name = "afternoon", start = "13:00", end = "19:00" },
+                { name = "evening", start = "19:00", end = "22:00" },
+            ]
+            days = [{ day = "MON" }, { day = "sabato", hours = [4.0, 0.0, 0.0] }]
+            coverage = [{ day = "MON", turn = "afternoon", min = 2 }]
+            closures = [{ name = "Santo patrono", month = 1, day = 24 }]
+
+            [[people]]
+            name = "Andrea"
+            surname = "Bonvissuto"
+            deadline = "2026-06-30"
+            preferences = [{ day = "MON", turn = "evening", rank = 2 }]
+            unavailable = [{ day = "SAT", turn = 0 }]
+        "#;
+        let timetable = from_toml(content).unwrap();
+        assert_eq!(timetable.days(), &[Day::Mon, Day::Sat]);
+        assert_eq!(timetable.turn_by_name("evening"), Some(Turn(2)));
+        assert_eq!(timetable.turn_end(1, 0), ClockTime::new(12, 30));
+        assert_eq!(timetable.get_coverage().unwrap()[0][0][1], Coverage::exactly(2));
+        //Saturday 24 January is the patron saint's day
+        assert_eq!(timetable.closure_of(0, 0), None);
+        assert_eq!(timetable.closure_of(0, 1).as_deref(), Some("Santo patrono"));
+        let person = &timetable.people[0];
+        assert_eq!(person.deadline, NaiveDate::from_ymd_opt(2026, 6, 30));
+        assert_eq!(person.preferences[0].weight, 0.5);
+        assert_eq!(person.unavailable[0].day, Day::Sat);
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let timetable = from_csv(CSV.as_bytes()).unwrap();
+        let json = serde_json::to_string(&TimetableFile::from_timetable(&timetable)).unwrap();
+        let again = from_json(&json).unwrap();
+        assert_eq!(again.days(), timetable.days());
+        assert_eq!(again.turns().len(), timetable.turns().len());
+        assert_eq!(again.people[0].preferences.len(), 3);
+        assert_eq!(again.people[0].unavailable.len(), 1);
+    }
+
+    #[test]
+    fn rejects_bad_records() {
+        let day = r#"{ "days": [{ "day": "MON", "hours": [4.0] }], "people": [] }"#;
+        assert!(matches!(from_json(day), Err(ScheduleError::InvalidInput { .. })));
+        let days = r#"{ "days": [], "people": [] }"#;
+        assert!(matches!(from_json(days), Err(ScheduleError::InvalidInput { .. })));
+        let turn = r#"{ "turns": [{ "name": "late", "start": "22:00", "end": "20:00" }], "people": [] }"#;
+        assert!(matches!(from_json(turn), Err(ScheduleError::InvalidInput { .. })));
+        let closure = r#"{ "closures": [{ "name": "nope", "month": 2, "day": 30 }], "people": [] }"#;
+        assert!(matches!(from_json(closure), Err(ScheduleError::InvalidInput { .. })));
+        let field = r#"{ "people": [], "holidays": [] }"#;
+        assert!(matches!(from_json(field), Err(ScheduleError::InvalidInput { .. })));
+    }
+
+    #[test]
+    fn week_hours_against_the_config() {
+        let person = |fields: &str| from_json(&format!(
+            r#"{{ "config": {{ "min_hours_per_week": 6.0, "max_hours_per_week": 10.0 }},
+                 "people": [{{ "name": "Andrea", "surname": "Bonvissuto", {} }}] }}"#, fields)).unwrap();
+        let invalid = |timetable: Timetable| matches!(timetable.get_people_preferences_and_rem_hours(),
+                                                      Err(ScheduleError::InvalidPerson { index: 0, .. }));
+        assert!(!invalid(person(r#""max_week_hours": 8.0, "target_week_hours": 7.0"#)));
+        //below the minimum of the config
+        assert!(invalid(person(r#""max_week_hours": 4.0"#)));
+        assert!(invalid(person(r#""target_week_hours": 12.0"#)));
+        assert!(invalid(person(r#""min_week_hours": 8.0, "target_week_hours": 7.0"#)));
+    }
+
+    #[test]
+    fn advance_moves_past_the_horizon() {
+        let mut file: TimetableFile = serde_json::from_str(
+            r#"{ "week": { "year": 2026, "week": 52 }, "weeks": 2, "people": [{ "name": "A", "surname": "B" }] }"#).unwrap();
+        file.advance(&[7.5]).unwrap();
+        assert_eq!(file.people[0].worked_hours, 7.5);
+        let week = file.week.as_ref().unwrap();
+        assert_eq!((week.year, week.week), (2027, 1));
+        assert!(file.advance(&[]).is_err());
+    }
+}
@@ -0,0 +1,282 @@
+use std::rc::Rc;
+use good_lp::{Expression, variable};
+use crate::types::scheduler::Scheduler;
+
+///a term of the function the scheduler minimises.
+///Terms can add auxiliary variables and constraints to the scheduler they are built for.
+pub trait ObjectiveTerm {
+    fn name(&self) -> &'static str;
+    fn expression(&self, scheduler: &mut Scheduler) -> Expression;
+}
+
+///weighted sum of objective terms
+#[derive(Clone)]
+pub struct Objective {
+    terms: Vec<(f64, Rc<dyn ObjectiveTerm>)>,
+}
+
+impl Default for Objective {
+    fn default() -> Self {
+        Self::new().with_term(1.0, RemainingHoursPreference)
+    }
+}
+
+impl Objective {
+    ///an objective without terms, add them with with_term/add_term
+    pub fn new() -> Self {
+        Self {
+            terms: vec![],
+        }
+    }
+    pub fn with_term(mut self, weight: f64, term: impl ObjectiveTerm + 'static) -> Self {
+        self.add_term(weight, term);
+        self
+    }
+    pub fn add_term(&mut self, weight: f64, term: impl ObjectiveTerm + 'static) -> &mut Self {
+        self.terms.push((weight, Rc::new(term)));
+        self
+    }
+    ///names and weights of the terms
+    pub fn terms(&self) -> Vec<(&'static str, f64)> {
+        self.terms.iter().map(|(w, t)| (t.name(), *w)).collect()
+    }
+    pub fn expression(&self, scheduler: &mut Scheduler) -> Expression {
+        let mut obj = Expression::default();
+        for (weight, term) in self.terms.iter() {
+            obj.add_mul(*weight, term.expression(scheduler));
+        }
+        obj
+    }
+}
+
+///preferred hours of the horizon, weighted by the remaining hours of each person
+#[derive(Copy, Clone)]
+pub struct RemainingHoursPreference;
+
+impl ObjectiveTerm for RemainingHoursPreference {
+    fn name(&self) -> &'static str {
+        "RemainingHoursPreference"
+    }
+    fn expression(&self, scheduler: &mut Scheduler) -> Expression {
+        //Objective function: min(SUM:hours_remaining*(hours_remaining-weighted_hours_this_week))
+        let mut obj = Expression::default();
+        for i in 0..scheduler.num_workers() {
+            let e = scheduler.remaining_hours(i) - preferred_hours(scheduler, i);
+            // rem_hours * (rem_hours - this_week_hours) ==> the outer gives importance to who has less hours
+            obj.add_mul(scheduler.remaining_hours(i), e);
+        }
+        obj
+    }
+}
+
+///preferred hours assigned, regardless of who gets them
+#[derive(Copy, Clone)]
+pub struct PreferenceSatisfaction;
+
+impl ObjectiveTerm for PreferenceSatisfaction {
+    fn name(&self) -> &'static str {
+        "PreferenceSatisfaction"
+    }
+    fn expression(&self, scheduler: &mut Scheduler) -> Expression {
+        let mut obj = Expression::default();
+        for i in 0..scheduler.num_workers() {
+            obj -= preferred_hours(scheduler, i);
+        }
+        obj
+    }
+}
+
+///the largest amount of hours anybody has left after the horizon
+#[derive(Copy, Clone)]
+pub struct HoursFairness;
+
+impl ObjectiveTerm for HoursFairness {
+    fn name(&self) -> &'static str {
+        "HoursFairness"
+    }
+    fn expression(&self, scheduler: &mut Scheduler) -> Expression {
+        //z >= rem_i - horizon_hours_i for everybody, minimising z lifts whoever is most behind
+        let z = scheduler.add_variable(variable());
+        let mut c = vec![];
+        for i in 0..scheduler.num_workers() {
+            let left = scheduler.remaining_hours(i) - scheduler.horizon_hours(i);
+            c.push(left.leq(z));
+        }
+        scheduler.add_constraint(self.name(), c);
+        z.into()
+    }
+}
+
+///days each person has to come to campus
+#[derive(Copy, Clone)]
+pub struct FewerCampusDays;
+
+impl ObjectiveTerm for FewerCampusDays {
+    fn name(&self) -> &'static str {
+        "FewerCampusDays"
+    }
+    fn expression(&self, scheduler: &mut Scheduler) -> Expression {
+        //y_i_d >= p_i_d_s: y is 1 as soon as the person works any shift of the day
+        let mut obj = Expression::default();
+        let mut c = vec![];
+        for i in 0..scheduler.num_workers() {
+            for w in 0..scheduler.num_weeks() {
+                for d in 0..scheduler.num_days() {
+                    let y = scheduler.add_variable(variable().min(0).max(1));
+                    for s in 0..scheduler.num_shifts() {
+                        c.push(Expression::from(scheduler.variable(i, w, d, s)).leq(y));
+                    }
+                    obj += y;
+                }
+            }
+        }
+        scheduler.add_constraint(self.name(), c);
+        obj
+    }
+}
+
+///distance of the horizon hours of each person from their share of the shift hours,
+///proportional to their remaining hours: keeps anybody from being starved week after week
+#[derive(Copy, Clone)]
+pub struct CumulativeFairness;
+
+impl ObjectiveTerm for CumulativeFairness {
+    fn name(&self) -> &'static str {
+        "CumulativeFairness"
+    }
+    fn expression(&self, scheduler: &mut Scheduler) -> Expression {
+        let mut shift_hours = 0.0;
+        for w in 0..scheduler.num_weeks() {
+            for d in 0..scheduler.num_days() {
+                for s in 0..scheduler.num_shifts() {
+                    shift_hours += scheduler.hours(w, d, s) * scheduler.coverage(w, d, s).min as f64;
+                }
+            }
+        }
+        let tot_remaining: f64 = (0..scheduler.num_workers())
+            .map(|i| scheduler.remaining_hours(i).max(0.0))
+            .sum();
+        if tot_remaining <= 0.0 {
+            return Expression::default();
+        }
+        //dev_i >= |horizon_hours_i - share_i|
+        let mut obj = Expression::default();
+        let mut c = vec![];
+        for i in 0..scheduler.num_workers() {
+            let share = shift_hours * scheduler.remaining_hours(i).max(0.0) / tot_remaining;
+            let dev = scheduler.add_variable(variable().min(0));
+            let hours = scheduler.horizon_hours(i);
+            c.push((hours.clone() - share).leq(dev));
+            c.push((share - hours).leq(dev));
+            obj += dev;
+        }
+        scheduler.add_constraint(self.name(), c);
+        obj
+    }
+}
+
+///distance of the weekly hours of each person from their target_week_hours, if set
+#[derive(Copy, Clone)]
+pub struct WeekHoursTarget;
+
+impl ObjectiveTerm for WeekHoursTarget {
+    fn name(&self) -> &'static str {
+        "WeekHoursTarget"
+    }
+    fn expression(&self, scheduler: &mut Scheduler) -> Expression {
+        //dev_i_w >= |week_hours_i_w - target_i|
+        let mut obj = Expression::default();
+        let mut c = vec![];
+        for i in 0..scheduler.num_workers() {
+            let Some(target) = scheduler.people()[i].target_week_hours else {
+                continue;
+            };
+            for w in 0..scheduler.num_weeks() {
+                let dev = scheduler.add_variable(variable().min(0));
+                let hours = scheduler.week_hours(i, w);
+                c.push((hours.clone() - target).leq(dev));
+                c.push((target - hours).leq(dev));
+                obj += dev;
+            }
+        }
+        scheduler.add_constraint(self.name(), c);
+        obj
+    }
+}
+
+///hours assigned past the remaining contract hours, allowed by RemainingHoursCap::max_overrun
+#[derive(Copy, Clone)]
+pub struct OverrunPenalty;
+
+impl ObjectiveTerm for OverrunPenalty {
+    fn name(&self) -> &'static str {
+        "OverrunPenalty"
+    }
+    fn expression(&self, scheduler: &mut Scheduler) -> Expression {
+        //over_i >= horizon_hours_i - rem_i, over_i >= 0
+        let mut obj = Expression::default();
+        let mut c = vec![];
+        for i in 0..scheduler.num_workers() {
+            let over = scheduler.add_variable(variable().min(0));
+            let rem = scheduler.remaining_hours(i).max(0.0);
+            c.push((scheduler.horizon_hours(i) - rem).leq(over));
+            obj += over;
+        }
+        scheduler.add_constraint(self.name(), c);
+        obj
+    }
+}
+
+///hours each person falls behind the weekly pace needed to meet their contract deadline,
+///weighted by that pace: the closer to the deadline, the more a missed hour costs
+#[derive(Clone)]
+pub struct DeadlineUrgency {
+    ///hours per week each person must work, 0 without a deadline
+    pace: Vec<f64>,
+}
+
+impl DeadlineUrgency {
+    pub fn new(pace: Vec<f64>) -> Self {
+        Self {
+            pace,
+        }
+    }
+}
+
+impl ObjectiveTerm for DeadlineUrgency {
+    fn name(&self) -> &'static str {
+        "DeadlineUrgency"
+    }
+    fn expression(&self, scheduler: &mut Scheduler) -> Expression {
+        //short_i >= min(pace_i * weeks, rem_i) - horizon_hours_i, short_i >= 0
+        let mut obj = Expression::default();
+        let mut c = vec![];
+        for (i, pace) in self.pace.iter().enumerate().take(scheduler.num_workers()) {
+            if *pace <= 0.0 {
+                continue;
+            }
+            let short = scheduler.add_variable(variable().min(0));
+            let target = f64::min(pace * scheduler.num_weeks() as f64, scheduler.remaining_hours(i));
+            c.push((target - scheduler.horizon_hours(i)).leq(short));
+            obj.add_mul(*pace, short);
+        }
+        scheduler.add_constraint(self.name(), c);
+        obj
+    }
+}
+
+//weighted hours: first choices count more, "prefer not" counts against
+fn preferred_hours(scheduler: &Scheduler, i: usize) -> Expression {
+    let mut wh = Expression::default();
+    for w in 0..scheduler.num_weeks() {
+        for d in 0..scheduler.num_days() {
+            for s in 0..scheduler.num_shifts() {
+                let weight = scheduler.preference(i, d, s);
+                if weight != 0.0 {
+                    wh.add_mul(weight * scheduler.hours(w, d, s), scheduler.variable(i, w, d, s));
+                }
+            }
+        }
+    }
+    wh
+}
@@ -1,28 +1,69 @@
-use std::fmt::format;
-use crate::types::timetable::{Day, Turn, TurnHours};
+use chrono::NaiveDate;
+use crate::types::timetable::{Day, Turn};
 
 #[derive(Copy, Clone)]
 pub struct Preference {
     pub day: Day,
     pub turn: Turn,
+    ///how much the shift is wanted: higher wins, negative means "prefer not"
+    pub weight: f64,
 }
 
 impl Preference {
     pub fn new(day: Day, turn: Turn) -> Self {
+        Self::with_weight(day, turn, 1.0)
+    }
+    pub fn with_weight(day: Day, turn: Turn, weight: f64) -> Self {
         Self {
             day,
             turn,
+            weight,
         }
     }
+    ///1 is the first choice, 2 the second and so on
+    pub fn ranked(day: Day, turn: Turn, rank: u32) -> Self {
+        Self::with_weight(day, turn, 1.0 / rank.max(1) as f64)
+    }
+    ///the person can work the shift but would rather not
+    pub fn avoid(day: Day, turn: Turn) -> Self {
+        Self::with_weight(day, turn, -1.0)
+    }
 }
 
+///a shift the person cannot work in any case
+#[derive(Copy, Clone)]
+pub struct Unavailability {
+    pub day: Day,
+    pub turn: Turn,
+}
+
+impl Unavailability {
+    pub fn new(day: Day, turn: Turn) -> Self {
+        Self {
+            day,
+            turn,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Person {
     pub name: String,
     pub surname: String,
+    ///soft: the scheduler rewards these shifts
     pub preferences: Vec<Preference>,
+    ///hard: the scheduler never assigns these shifts
+    pub unavailable: Vec<Unavailability>,
     pub tot_hours: f64,
     //amount of hours before timetable
     pub worked_hours: f64,
+    ///last day of the contract, tot_hours must be worked by then
+    pub deadline: Option<NaiveDate>,
+    ///weekly bounds of this person, None falls back to the timetable config
+    pub min_week_hours: Option<f64>,
+    pub max_week_hours: Option<f64>,
+    ///hours per week the person would like to work, if any
+    pub target_week_hours: Option<f64>,
 }
 
 impl Person {
@@ -31,8 +72,13 @@ impl Person {
             name: name.to_string(),
             surname: surname.to_string(),
             preferences: vec![],
+            unavailable: vec![],
             tot_hours: 150.0,
             worked_hours: 0.0,
+            deadline: None,
+            min_week_hours: None,
+            max_week_hours: None,
+            target_week_hours: None,
         }
     }
     pub fn with_preferences(name: &str, surname: &str, preferences: Vec<Preference>, worked_hours: f64) -> Self {
@@ -40,8 +86,13 @@ impl Person {
             name: name.to_string(),
             surname: surname.to_string(),
             preferences,
+            unavailable: vec![],
             tot_hours: 150.0,
             worked_hours,
+            deadline: None,
+            min_week_hours: None,
+            max_week_hours: None,
+            target_week_hours: None,
         }
     }
     pub fn with_all(name: &str, surname: &str, preferences: Vec<Preference>, worked_hours: f64, tot_hours: f64) -> Self {
@@ -49,15 +100,76 @@ impl Person {
             name: name.to_string(),
             surname: surname.to_string(),
             preferences,
+            unavailable: vec![],
             tot_hours,
             worked_hours,
+            deadline: None,
+            min_week_hours: None,
+            max_week_hours: None,
+            target_week_hours: None,
         }
     }
+    pub fn with_unavailability(mut self, unavailable: Vec<Unavailability>) -> Self {
+        self.unavailable = unavailable;
+        self
+    }
+    pub fn with_deadline(mut self, deadline: NaiveDate) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+    pub fn with_week_hours(mut self, min: f64, max: f64) -> Self {
+        self.min_week_hours = Some(min);
+        self.max_week_hours = Some(max);
+        self
+    }
+    pub fn with_target_week_hours(mut self, target: f64) -> Self {
+        self.target_week_hours = Some(target);
+        self
+    }
+    ///weekly (min, max) hours of this person, the given defaults where not set
+    pub fn week_hours_bounds(&self, min: f64, max: f64) -> (f64, f64) {
+        (self.min_week_hours.unwrap_or(min), self.max_week_hours.unwrap_or(max))
+    }
+    ///first two letters of name and surname (fewer if they are shorter)
     pub fn acronym(&self) -> String {
-        format!("{}{}", self.name.get(0..=1).unwrap(), self.surname.get(0..=1).unwrap())
+        self.name.chars().take(2).chain(self.surname.chars().take(2)).collect()
+    }
+    ///checks the data a scheduler relies on, returning the reason it is unusable
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("empty name".to_string());
+        }
+        if self.surname.trim().is_empty() {
+            return Err(format!("{}: empty surname", self.name));
+        }
+        if !self.tot_hours.is_finite() || self.tot_hours < 0.0 {
+            return Err(format!("{}: invalid total hours {}", self.acronym(), self.tot_hours));
+        }
+        if !self.worked_hours.is_finite() || self.worked_hours < 0.0 {
+            return Err(format!("{}: invalid worked hours {}", self.acronym(), self.worked_hours));
+        }
+        for hours in [self.min_week_hours, self.max_week_hours, self.target_week_hours].into_iter().flatten() {
+            if !hours.is_finite() || hours < 0.0 {
+                return Err(format!("{}: invalid weekly hours {}", self.acronym(), hours));
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_week_hours, self.max_week_hours) {
+            if min > max {
+                return Err(format!("{}: weekly minimum {} above maximum {}", self.acronym(), min, max));
+            }
+        }
+        if let Some(p) = self.preferences.iter().find(|p| !p.weight.is_finite()) {
+            return Err(format!("{}: invalid preference weight {} on {} turn #{}",
+                               self.acronym(), p.weight, p.day.name(), p.turn.0));
+        }
+        for u in self.unavailable.iter() {
+            let clash = self.preferences.iter()
+                .any(|p| p.weight > 0.0 && p.day == u.day && p.turn == u.turn);
+            if clash {
+                return Err(format!("{}: both preferred and unavailable on {} turn #{}",
+                                   self.acronym(), u.day.name(), u.turn.0));
+            }
+        }
+        Ok(())
     }
 }
-
-
-
-
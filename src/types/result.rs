@@ -0,0 +1,24 @@
+///hours assigned to a person by a run of the scheduler
+#[derive(Clone, Debug)]
+pub struct PersonStats {
+    ///hours of each week of the horizon
+    pub weekly_hours: Vec<f64>,
+    ///hours of the whole horizon
+    pub hours: f64,
+    ///contract hours still to be worked after the horizon
+    pub remaining_hours: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct ScheduleStats {
+    ///indexed like Timetable::people
+    pub people: Vec<PersonStats>,
+    pub objective: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct ScheduleResult {
+    ///people (indexes in Timetable::people) assigned to each [week][day][turn]
+    pub assignments: Vec<Vec<Vec<Vec<usize>>>>,
+    pub stats: ScheduleStats,
+}
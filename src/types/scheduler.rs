@@ -1,101 +1,138 @@
-use std::collections::HashMap;
 use std::mem;
-use std::ops::Sub;
-use good_lp::{Constraint, constraint, default_solver, Expression, ProblemVariables, Solution, SolverModel, Variable, VariableDefinition, variables};
+use good_lp::{Constraint, default_solver, Expression, ProblemVariables, Solution, SolverModel, Variable, VariableDefinition, variables};
 use good_lp::solvers::coin_cbc::{CoinCbcProblem, CoinCbcSolution};
-use good_lp::variable::FormatWithVars;
+use crate::types::constraint::ScheduleConstraint;
+use crate::types::diagnosis;
+use crate::types::error::ScheduleError;
+use crate::types::objective::Objective;
 use crate::types::person::Person;
-use crate::types::timetable::Timetable;
-
-pub enum ConstraintType {
-    MaxOnePersonPerShift,
-    MinMaxWeekHoursPerPerson(f64, f64),
-    NoConsecutiveShifts,
-}
+use crate::types::result::{PersonStats, ScheduleResult, ScheduleStats};
+use crate::types::timetable::{Coverage, Timetable};
 
 pub struct Scheduler {
-    ///model constants
-    max_hours_per_week: usize,
-    min_hours_per_week: usize,
+    ///model constants, derived from the timetable in setup
     num_workers: usize,
+    num_weeks: usize,
     num_days: usize,
     num_shifts: usize,
     model: ProblemVariables,
+    people: Vec<Person>,
     rem_hours: Vec<f64>,
-    preferences: Vec<Vec<Vec<bool>>>,
-    ///each constraint is, in general an array of constraints
-    constraints: Vec<Vec<Constraint>>,
-    // objective_function: Option<Expression>,
-    people_var: Vec<Vec<Vec<Variable>>>,
+    ///hours a person may work past their contract
+    max_overrun: f64,
+    ///preference weight of each [person][day][shift], 0 when not expressed (same every week)
+    preferences: Vec<Vec<Vec<f64>>>,
+    ///shifts each person cannot work [person][day][shift] (same every week)
+    unavailable: Vec<Vec<Vec<bool>>>,
+    ///hours of each [week][day][shift] as read from the timetable
+    hours: Vec<Vec<Vec<f64>>>,
+    ///headcount required on each [week][day][shift]
+    coverage: Vec<Vec<Vec<Coverage>>>,
+    ///each constraint is, in general an array of constraints, named after its group
+    constraints: Vec<(&'static str, Vec<Constraint>)>,
+    objective_function: Option<Expression>,
+    ///choice variables [person][week][day][shift]
+    people_var: Vec<Vec<Vec<Vec<Variable>>>>,
 }
 
 impl Default for Scheduler {
     fn default() -> Self {
         Self {
-            max_hours_per_week: 0,
-            min_hours_per_week: 0,
             num_workers: 0,
+            num_weeks: 0,
             num_days: 0,
             num_shifts: 0,
             model: variables!(),
+            people: vec!(),
             constraints: vec!(),
             preferences: vec!(),
+            unavailable: vec!(),
             rem_hours: vec!(),
-            // objective_function: None,
+            max_overrun: 0.0,
+            hours: vec!(),
+            coverage: vec!(),
+            objective_function: None,
             people_var: vec![],
         }
     }
 }
 
 impl Scheduler {
-    pub fn new(max_hours_per_week: usize,
-               min_hours_per_week: usize,
-               num_workers: usize,
-               num_days: usize,
-               num_shifts: usize,
-    ) -> Self {
+    pub fn new() -> Self {
         Self {
-            max_hours_per_week,
-            min_hours_per_week,
-            num_workers,
-            num_days,
-            num_shifts,
             ..Default::default()
         }
     }
-    pub fn setup(&mut self, timetable: &Timetable) -> &mut Self {
+    pub fn setup(&mut self, timetable: &Timetable) -> Result<&mut Self, ScheduleError> {
+        //MODEL DIMENSIONS
+        let hours = timetable.get_turn_hours();
+        self.num_workers = timetable.people.len();
+        self.num_weeks = hours.len();
+        self.num_days = timetable.days().len();
+        self.num_shifts = timetable.turns().len();
+        //CHECK FOR BAD SETUP
+        for week in hours.iter() {
+            check_dimension("days", self.num_days, week.len())?;
+            for h in week.iter() {
+                check_dimension("shifts", self.num_shifts, h.len())?;
+            }
+        }
         //GET PREFERENCES AND REMAINING HOURS
-        let (pref, rem) = timetable.get_people_preferences_and_rem_hours();
+        let (pref, rem) = timetable.get_people_preferences_and_rem_hours()?;
         self.add_preferences(pref);
+        self.add_unavailability(timetable.get_people_unavailability()?);
         self.add_remaining_hours(rem);
+        self.max_overrun = timetable.config.max_overrun_hours.max(0.0);
+        self.add_turn_hours(hours);
+        self.add_coverage(timetable.get_coverage()?);
         self.add_variables_to_model(&timetable.people);
-        self
+        self.people = timetable.people.clone();
+        Ok(self)
     }
     pub fn add_remaining_hours(&mut self, remaining_hours: Vec<f64>) -> &mut Self {
         self.rem_hours = remaining_hours;
         self
     }
-    pub fn add_preferences(&mut self, preferences: Vec<Vec<Vec<bool>>>) -> &mut Self {
+    pub fn add_turn_hours(&mut self, hours: Vec<Vec<Vec<f64>>>) -> &mut Self {
+        self.hours = hours;
+        self
+    }
+    pub fn add_coverage(&mut self, coverage: Vec<Vec<Vec<Coverage>>>) -> &mut Self {
+        self.coverage = coverage;
+        self
+    }
+    pub fn add_unavailability(&mut self, unavailable: Vec<Vec<Vec<bool>>>) -> &mut Self {
+        self.unavailable = unavailable;
+        self
+    }
+    pub fn add_preferences(&mut self, preferences: Vec<Vec<Vec<f64>>>) -> &mut Self {
         self.preferences = preferences;
         self
     }
 
-    fn add_constraint(&mut self, constraint: Vec<Constraint>) -> &mut Self {
-        self.constraints.push(constraint);
+    pub fn add_constraint(&mut self, name: &'static str, constraint: Vec<Constraint>) -> &mut Self {
+        self.constraints.push((name, constraint));
         self
     }
 
-    fn add_variables_to_model(&mut self, people: &Vec<Person>) -> &mut Self {
+    ///names of the constraint groups added so far
+    pub fn constraint_names(&self) -> Vec<&'static str> {
+        self.constraints.iter().map(|(name, _)| *name).collect()
+    }
+
+    fn add_variables_to_model(&mut self, people: &[Person]) -> &mut Self {
         // Choice variable => indicates which person is going to be working on each shift
-        let mut p = vec![vec![Vec::<Variable>::new(); self.num_days]; self.num_workers];
+        let mut p = vec![vec![vec![Vec::<Variable>::new(); self.num_days]; self.num_weeks]; self.num_workers];
 
         // Add all the variables to the model
-        for i in 0..self.num_workers {
-            for d in 0..self.num_days {
-                for s in 0..self.num_shifts {
-                    let y =
-                        VariableDefinition::new().binary().name(format!("p_{}_{}_{}_{}", i, d, s, people[i].acronym()));
-                    p[i][d].push(self.model.add(y));
+        for (i, weeks) in p.iter_mut().enumerate() {
+            for (w, days) in weeks.iter_mut().enumerate() {
+                for (d, shifts) in days.iter_mut().enumerate() {
+                    for s in 0..self.num_shifts {
+                        let y = VariableDefinition::new().binary()
+                            .name(format!("p_{}_{}_{}_{}_{}", i, w, d, s, people[i].acronym()));
+                        shifts.push(self.model.add(y));
+                    }
                 }
             }
         }
@@ -103,136 +140,109 @@ impl Scheduler {
         self
     }
 
-    // pub fn define_objective_function(&mut self, objective: Expression) -> &mut Self {
-    //     self.objective_function = Some(objective);
-    //     self
-    // }
-    pub fn set_constraint(&mut self, constraint: ConstraintType) {
-        match constraint {
-            ConstraintType::MaxOnePersonPerShift =>
-                self.add_constraint(set_max_one_person_per_shift(&self)),
-            ConstraintType::MinMaxWeekHoursPerPerson(min, max) =>
-                self.add_constraint(set_min_max_hours_per_week(&self, min, max)),
-            ConstraintType::NoConsecutiveShifts =>
-                self.add_constraint(set_no_consecutive_shifts(&self))
-        };
-    }
-    pub fn schedule(&mut self) -> &mut Self {
-        let model = mem::take(&mut self.model);
-        let mut problem =
-            model.minimise(default_objective_function(&self))
-                .using(default_solver);
-
-        let constraints = mem::take(&mut self.constraints);
-        for c in constraints.into_iter() {
-            add_vec_contraints(&mut problem, c);
-        }
-
-        let people_var = mem::take(&mut self.people_var);
-        let solution = problem.solve();
-        match solution {
-            Ok(sol) => {
-                print_sol(&sol);
-                print_person_caledar(&sol, people_var, &self.preferences, &self.rem_hours);
-
-                //WE CAN DO BETTER THAN THE ABOVE
-            }
-            Err(e) => {
-                println!("{}", e)
-            }
-        }
+    pub fn define_objective_function(&mut self, objective: Expression) -> &mut Self {
+        self.objective_function = Some(objective);
         self
     }
-}
+    pub fn set_objective(&mut self, objective: &Objective) -> &mut Self {
+        let obj = objective.expression(self);
+        self.define_objective_function(obj)
+    }
+    ///auxiliary variable for constraints and objective terms
+    pub fn add_variable(&mut self, definition: VariableDefinition) -> Variable {
+        self.model.add(definition)
+    }
+    pub fn set_constraint(&mut self, constraint: &dyn ScheduleConstraint) {
+        let c = constraint.constraints(self);
+        self.add_constraint(constraint.name(), c);
+    }
 
-//constraints functions
-fn set_max_one_person_per_shift(scheduler: &Scheduler) -> Vec<Constraint> {
-    //Constraint 1: One person per shift (skip if no preference was provided)
-    let mut c1 = vec![];
-    for d in 0..scheduler.num_days {
-        for s in 0..scheduler.num_shifts {
-            let mut e = Expression::default();
-            for i in 0..scheduler.num_workers {
-                e += scheduler.people_var[i][d][s].clone(); //it is just an index to a variable of the problem so we are ok
+    //read access for constraints
+    pub fn num_workers(&self) -> usize {
+        self.num_workers
+    }
+    ///weeks of the planning horizon
+    pub fn num_weeks(&self) -> usize {
+        self.num_weeks
+    }
+    pub fn num_days(&self) -> usize {
+        self.num_days
+    }
+    pub fn num_shifts(&self) -> usize {
+        self.num_shifts
+    }
+    ///choice variable of person i on (week, day, shift)
+    pub fn variable(&self, i: usize, week: usize, day: usize, shift: usize) -> Variable {
+        self.people_var[i][week][day][shift]
+    }
+    pub fn people(&self) -> &[Person] {
+        &self.people
+    }
+    pub fn hours(&self, week: usize, day: usize, shift: usize) -> f64 {
+        self.hours[week][day][shift]
+    }
+    pub fn coverage(&self, week: usize, day: usize, shift: usize) -> Coverage {
+        self.coverage[week][day][shift]
+    }
+    pub fn preference(&self, i: usize, day: usize, shift: usize) -> f64 {
+        self.preferences[i][day][shift]
+    }
+    pub fn is_unavailable(&self, i: usize, day: usize, shift: usize) -> bool {
+        self.unavailable[i][day][shift]
+    }
+    pub fn remaining_hours(&self, i: usize) -> f64 {
+        self.rem_hours[i]
+    }
+    ///weekly minimum person i can meet with the hours left to them (plus the overrun):
+    ///min, or 0 when they do not fit a shift or min in every open week
+    pub fn reachable_week_min(&self, i: usize, min: f64) -> f64 {
+        let rem = self.rem_hours[i];
+        let cap = if rem > 0.0 { rem + self.max_overrun } else { 0.0 };
+        diagnosis::reachable_week_min(min, cap, &self.hours, &self.coverage, &self.unavailable[i])
+    }
+    ///hours worked by person i in a week, as an expression of the variables
+    pub fn week_hours(&self, i: usize, week: usize) -> Expression {
+        let mut e = Expression::default();
+        for d in 0..self.num_days {
+            for s in 0..self.num_shifts {
+                e.add_mul(self.hours[week][d][s], self.people_var[i][week][d][s]);
             }
-            c1.push(e.eq(1)); //this restricts the shift to only one person at time (for now)
         }
+        e
     }
-    c1
-}
-
-fn set_min_max_hours_per_week(scheduler: &Scheduler, min: f64, max: f64) -> Vec<Constraint> {
-    let mut c2 = vec![];
-
-    for i in 0..scheduler.num_workers {
+    ///hours worked by person i in the whole horizon
+    pub fn horizon_hours(&self, i: usize) -> Expression {
         let mut e = Expression::default();
-        for d in 0..scheduler.num_days {
-            for s in 0..scheduler.num_shifts {
-                //build a gethours function to retrieve dynamically
-                let hours = match s {
-                    0 => 4,
-                    1 => match d {
-                        0..=3 => 6,
-                        4 => 5,
-                        _ => unreachable!()
-                    }
-                    _ => unreachable!()
-                };
-                e.add_mul(hours, scheduler.people_var[i][d][s].clone());
-            }
+        for w in 0..self.num_weeks {
+            e += self.week_hours(i, w);
         }
-        c2.push(e.clone().geq(min));
-        c2.push(e.leq(max)); //optionally can be increased (per person or globally)
+        e
     }
-    c2
-}
+    pub fn schedule(&mut self) -> Result<ScheduleResult, ScheduleError> {
+        if self.objective_function.is_none() {
+            self.set_objective(&Objective::default());
+        }
+        let model = mem::take(&mut self.model);
+        let objective = self.objective_function.take().unwrap_or_default();
+        let mut problem =
+            model.minimise(objective.clone())
+                .using(default_solver);
 
-fn set_no_consecutive_shifts(scheduler: &Scheduler) -> Vec<Constraint> {
-    let mut c3 = vec![];
-    for i in 0..scheduler.num_workers {
-        for d in 0..scheduler.num_days {
-            let mut e = Expression::default();
-            for s in 0..scheduler.num_shifts {
-                e += scheduler.people_var[i][d][s].clone();
-            }
-            c3.push(e.clone().leq(1));
+        let constraints = mem::take(&mut self.constraints);
+        for (_, c) in constraints.into_iter() {
+            add_vec_contraints(&mut problem, c);
         }
+
+        let solution = problem.solve()?;
+        Ok(output_result(&solution, &self.people_var, &self.rem_hours, &self.hours, &objective))
     }
-    c3
 }
 
-fn default_objective_function(scheduler: &Scheduler) -> Expression {
-    //Objective function: min(SUM:hours_remaining*(hours_remaining-hours_this_week))
-    let mut obj = Expression::default();
-    for i in 0..scheduler.num_workers {
-        let mut wh = Expression::default();
-        for d in 0..scheduler.num_days {
-            for s in 0..scheduler.num_shifts {
-                if scheduler.preferences[i][d][s] {
-                    //TODO: use timetable hours (some days might not be available or some hours can differ)
-                    match s {
-                        0 => { wh.add_mul(4, scheduler.people_var[i][d][s].clone()) }
-                        1 => {
-                            match d {
-                                0..=3 => {
-                                    wh.add_mul(6, scheduler.people_var[i][d][s]);
-                                }
-                                4 => {
-                                    wh.add_mul(5, scheduler.people_var[i][d][s].clone());
-                                }
-                                _ => unreachable!()
-                            }
-                        }
-                        _ => { unreachable!() }
-                    }
-                }
-            }
-        }
-        let e = scheduler.rem_hours[i] - wh;
-        // rem_hours * (rem_hours - this_week_hours) ==> the outer gives importance to who has less hours
-        obj.add_mul(scheduler.rem_hours[i], e);
+fn check_dimension(what: &'static str, expected: usize, found: usize) -> Result<(), ScheduleError> {
+    if expected != found {
+        return Err(ScheduleError::DimensionMismatch { what, expected, found });
     }
-    obj
+    Ok(())
 }
 
 fn add_vec_contraints(prob: &mut CoinCbcProblem, constraints: Vec<Constraint>) {
@@ -241,61 +251,49 @@ fn add_vec_contraints(prob: &mut CoinCbcProblem, constraints: Vec<Constraint>) {
     }
 }
 
-fn print_sol(solution: &CoinCbcSolution) {
-    solution.model().print_solution()
-}
+fn output_result(solution: &CoinCbcSolution, variables: &[Vec<Vec<Vec<Variable>>>],
+                 remaining: &[f64], hours: &[Vec<Vec<f64>>], objective: &Expression)
+                 -> ScheduleResult {
+    let mut assignments: Vec<Vec<Vec<Vec<usize>>>> = hours.iter()
+        .map(|week| week.iter().map(|day| vec![vec![]; day.len()]).collect())
+        .collect();
+    let mut people = Vec::with_capacity(variables.len());
 
-fn print_person_caledar(solution: &CoinCbcSolution, variables: Vec<Vec<Vec<Variable>>>,
-                        preferences: &Vec<Vec<Vec<bool>>>, remaining: &Vec<f64>) {
     for i in 0..variables.len() {
-        let mut tot_hours = 0f64;
-        println!("person:{}", i);
-        for d in 0..5 {
-            let t1 = solution.eval(variables[i][d][0]);
-            let t2 = solution.eval(variables[i][d][1]);
-            let p1 = if preferences[i][d][0] { 'x' } else { ' ' };
-            let p2 = if preferences[i][d][1] { 'x' } else { ' ' };
-            println!("d{}: [{}][{}] - [{}][{}]", d, t1, t2, p1, p2);
-            tot_hours += get_hours_by_day_turn(t1, d, 0);
-            tot_hours += get_hours_by_day_turn(t2, d, 1);
+        let mut weekly_hours = vec![0f64; hours.len()];
+        for w in 0..hours.len() {
+            for d in 0..hours[w].len() {
+                for s in 0..hours[w][d].len() {
+                    let assigned = is_assigned(solution.value(variables[i][w][d][s]));
+                    if assigned {
+                        assignments[w][d][s].push(i);
+                    }
+                    weekly_hours[w] += get_hours_by_day_turn(assigned, &hours[w], d, s);
+                }
+            }
         }
-        println!("TOT WEEK HOURS: {:02}h", tot_hours);
-        println!("REMAINING: {:02}h\n", remaining[i] - tot_hours);
+        let tot_hours: f64 = weekly_hours.iter().sum();
+        people.push(PersonStats {
+            weekly_hours,
+            hours: tot_hours,
+            remaining_hours: remaining[i] - tot_hours,
+        });
+    }
+    ScheduleResult {
+        assignments,
+        stats: ScheduleStats {
+            people,
+            objective: solution.eval(objective),
+        },
     }
 }
 
-fn output_result(solution: &CoinCbcSolution, variables: Vec<Vec<Vec<Variable>>>,
-                 preferences: &Vec<Vec<Vec<bool>>>, remaining: &Vec<f64>)
-                 -> (Vec<(usize, usize)>, Vec<(f64, f64)>) {
-    let mut tot_rem: Vec<(f64, f64)> = Vec::new();
-    let mut caledar: Vec<(usize, usize)> = Vec::new();
-
-    for i in 0..variables.len() {
-        let mut tot_hours = 0f64;
-        for d in 0..5 {
-            let t1 = solution.eval(variables[i][d][0]);
-            let t2 = solution.eval(variables[i][d][1]);
-            tot_hours += get_hours_by_day_turn(t1, d, 0);
-            tot_hours += get_hours_by_day_turn(t2, d, 1);
-        }
-        tot_rem.push((tot_hours, remaining[i] - tot_hours));
-        //TODO: complete
-        // caledar.push(())
-    }
-    (caledar, tot_rem)
+//binary variables may come back from the solver as 0.9999...
+fn is_assigned(variable: f64) -> bool {
+    variable > 0.5
 }
 
-fn get_hours_by_day_turn(variable: f64, day: usize, turn: usize) -> f64 {
-    if variable != 1f64 { return 0.0; }
-    match turn {
-        0 => { 4.0 }
-        1 => {
-            match day {
-                0..=3 => { 6.0 }
-                4 => { 5.0 }
-                _ => unreachable!()
-            }
-        }
-        _ => { unreachable!() }
-    }
-}
\ No newline at end of file
+fn get_hours_by_day_turn(assigned: bool, hours: &[Vec<f64>], day: usize, turn: usize) -> f64 {
+    if !assigned { return 0.0; }
+    hours[day][turn]
+}
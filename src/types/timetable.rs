@@ -1,59 +1,241 @@
+use std::fmt::{Display, Formatter};
+use chrono::{Days, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use crate::types::calendar;
+use crate::types::calendar::Closure;
+use crate::types::diagnosis;
+use crate::types::diagnosis::{DeadlineStatus, Diagnosis, Issue};
+use crate::types::error::ScheduleError;
+use crate::types::export::terminal;
+use crate::types::objective::{CumulativeFairness, DeadlineUrgency, Objective, OverrunPenalty, WeekHoursTarget};
 use crate::types::person::Person;
-use crate::types::scheduler;
-use crate::types::scheduler::{ConstraintType, Scheduler};
+use crate::types::result::{ScheduleResult, ScheduleStats};
+use std::rc::Rc;
+use crate::types::constraint::{MinMaxWeekHoursPerPerson, RemainingHoursCap, RespectUnavailability, ScheduleConstraint, ShiftCoverage};
+use crate::types::scheduler::Scheduler;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Day {
     Mon = 0,
     Tue = 1,
     Wed = 2,
     Thu = 3,
     Fri = 4,
+    Sat = 5,
+    Sun = 6,
 }
 
 impl Day {
-    fn name(&self) -> &'static str {
+    pub const ALL: [Day; 7] = [Day::Mon, Day::Tue, Day::Wed, Day::Thu, Day::Fri, Day::Sat, Day::Sun];
+
+    pub fn name(&self) -> &'static str {
         match self {
             Self::Mon => "MON",
             Self::Tue => "TUE",
             Self::Wed => "WED",
             Self::Thu => "THU",
-            Self::Fri => "FRI"
+            Self::Fri => "FRI",
+            Self::Sat => "SAT",
+            Self::Sun => "SUN"
         }
     }
+    ///weekday index, MON => 0 ... SUN => 6
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+    ///accepts English and Italian names, full or abbreviated, in any case
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        let day = match name.as_str() {
+            "mon" | "monday" | "lun" | "lunedì" | "lunedi" => Self::Mon,
+            "tue" | "tuesday" | "mar" | "martedì" | "martedi" => Self::Tue,
+            "wed" | "wednesday" | "mer" | "mercoledì" | "mercoledi" => Self::Wed,
+            "thu" | "thursday" | "gio" | "giovedì" | "giovedi" => Self::Thu,
+            "fri" | "friday" | "ven" | "venerdì" | "venerdi" => Self::Fri,
+            "sat" | "saturday" | "sab" | "sabato" => Self::Sat,
+            "sun" | "sunday" | "dom" | "domenica" => Self::Sun,
+            _ => return None
+        };
+        Some(day)
+    }
 }
 
-#[derive(Copy, Clone)]
-pub enum Turn {
-    Morning,
-    Afternoon,
+impl Display for Day {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+///index of a turn in Timetable::turns
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Turn(pub usize);
+
+impl Turn {
+    //turns of the default timetable
+    pub const MORNING: Turn = Turn(0);
+    pub const AFTERNOON: Turn = Turn(1);
+}
+
+///time of the day, minutes precision
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ClockTime {
+    pub hour: u8,
+    pub minute: u8,
+}
+
+impl ClockTime {
+    pub fn new(hour: u8, minute: u8) -> Self {
+        Self {
+            hour,
+            minute,
+        }
+    }
+    pub fn minutes(&self) -> u32 {
+        self.hour as u32 * 60 + self.minute as u32
+    }
+    pub fn from_minutes(minutes: u32) -> Self {
+        Self::new((minutes / 60) as u8, (minutes % 60) as u8)
+    }
+    pub fn add_hours(&self, hours: f64) -> Self {
+        Self::from_minutes(self.minutes() + (hours * 60.0).round() as u32)
+    }
+}
+
+impl Display for ClockTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+///a turn of the day, e.g. an evening shift during exams or half of a split afternoon
+#[derive(Clone, Debug)]
+pub struct TurnDef {
+    pub name: String,
+    pub start: ClockTime,
+    pub end: ClockTime,
+}
+
+impl TurnDef {
+    pub fn new(name: &str, start: ClockTime, end: ClockTime) -> Self {
+        Self {
+            name: name.to_string(),
+            start,
+            end,
+        }
+    }
+    pub fn hours(&self) -> f64 {
+        (self.end.minutes() as f64 - self.start.minutes() as f64) / 60.0
+    }
+}
+
+///how many people must work a shift
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Coverage {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl Default for Coverage {
+    fn default() -> Self {
+        Self::exactly(1)
+    }
+}
+
+impl Coverage {
+    pub fn new(min: usize, max: usize) -> Self {
+        Self {
+            min,
+            max,
+        }
+    }
+    pub fn exactly(n: usize) -> Self {
+        Self::new(n, n)
+    }
 }
 
 #[derive(Copy, Clone)]
-pub struct TurnHours(f32);
+pub struct TurnHours(pub f32);
+
+impl TurnHours {
+    pub fn hours(&self) -> f64 {
+        self.0 as f64
+    }
+}
+
+///tunable values of the model
+#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TimetableConfig {
+    pub min_hours_per_week: f64,
+    pub max_hours_per_week: f64,
+    ///hours a person may work past their contract, each one penalised in the objective
+    pub max_overrun_hours: f64,
+}
+
+impl Default for TimetableConfig {
+    fn default() -> Self {
+        Self {
+            min_hours_per_week: 1.0,
+            max_hours_per_week: 12.0,
+            max_overrun_hours: 0.0,
+        }
+    }
+}
+
+///preference weights indexed as [person][day][turn]
+pub type PreferenceGrid = Vec<Vec<Vec<f64>>>;
 
 pub struct Timetable {
-    computed: Option<Vec<(Person, Person)>>,
+    //people assigned to each [week][day][turn]
+    computed: Option<Vec<Vec<Vec<Vec<usize>>>>>,
     //stats per person
-    stats: Option<()>,
-    //hours per turn
-    base: Vec<(TurnHours, TurnHours)>,
+    stats: Option<ScheduleStats>,
+    //monday of the first ISO week being scheduled, None for abstract weeks
+    week_start: Option<NaiveDate>,
+    //weeks of the planning horizon
+    weeks: usize,
+    //local closures on top of the national holidays
+    closures: Vec<(Closure, String)>,
+    //open days, in weekday order
+    days: Vec<Day>,
+    //turns of every day
+    turns: Vec<TurnDef>,
+    //hours per [day][turn] (day as position in days), 0 when the turn is closed that day
+    base: Vec<Vec<TurnHours>>,
+    //headcount of the turns that differ from Coverage::default()
+    coverage: Vec<(Day, Turn, Coverage)>,
     //people with preferences
     pub people: Vec<Person>,
+    pub config: TimetableConfig,
+    //site-specific constraints added on top of the built-in ones
+    constraints: Vec<Rc<dyn ScheduleConstraint>>,
+    //None picks a default depending on the horizon
+    objective: Option<Objective>,
 }
 
 impl Default for Timetable {
     fn default() -> Self {
         Self {
             people: vec![],
-            base: vec![(TurnHours(4f32), TurnHours(6f32)),//LUN
-                       (TurnHours(4f32), TurnHours(6f32)),//MAR
-                       (TurnHours(4f32), TurnHours(6f32)),//MER
-                       (TurnHours(4f32), TurnHours(6f32)),//GIO
-                       (TurnHours(4f32), TurnHours(5f32)),//VEN
+            week_start: None,
+            weeks: 1,
+            closures: vec![],
+            days: vec![Day::Mon, Day::Tue, Day::Wed, Day::Thu, Day::Fri],
+            turns: vec![TurnDef::new("morning", ClockTime::new(8, 30), ClockTime::new(12, 30)),
+                        TurnDef::new("afternoon", ClockTime::new(13, 0), ClockTime::new(19, 0)),
             ],
+            base: vec![vec![TurnHours(4f32), TurnHours(6f32)],//LUN
+                       vec![TurnHours(4f32), TurnHours(6f32)],//MAR
+                       vec![TurnHours(4f32), TurnHours(6f32)],//MER
+                       vec![TurnHours(4f32), TurnHours(6f32)],//GIO
+                       vec![TurnHours(4f32), TurnHours(5f32)],//VEN
+            ],
+            coverage: vec![],
             stats: None,
             computed: None,
+            config: TimetableConfig::default(),
+            constraints: vec![],
+            objective: None,
         }
     }
 }
@@ -64,11 +246,137 @@ impl Timetable {
             ..Default::default()
         }
     }
-    pub fn with_turnhours(base: &[(TurnHours, TurnHours)]) -> Self {
-        Self {
-            base: base.to_vec(),
-            ..Default::default()
+    ///hours of the default days and turns, one row per open day
+    pub fn with_turnhours(base: &[Vec<TurnHours>]) -> Result<Self, ScheduleError> {
+        let mut timetable = Self::default();
+        timetable.set_turnhours(base.to_vec())?;
+        Ok(timetable)
+    }
+    ///every day gets the given turns with their full length
+    pub fn with_turns(turns: Vec<TurnDef>) -> Self {
+        let mut timetable = Self::default();
+        let day: Vec<TurnHours> = turns.iter().map(|t| TurnHours(t.hours() as f32)).collect();
+        timetable.base = vec![day; timetable.days.len()];
+        timetable.turns = turns;
+        timetable
+    }
+    ///timetable for a concrete ISO week: national holidays and closures are closed
+    pub fn for_week(year: i32, week: u32) -> Result<Self, ScheduleError> {
+        let mut timetable = Self::default();
+        timetable.set_week(year, week)?;
+        Ok(timetable)
+    }
+    ///plans `weeks` consecutive ISO weeks starting from (year, week) in a single model
+    pub fn for_weeks(year: i32, week: u32, weeks: usize) -> Result<Self, ScheduleError> {
+        let mut timetable = Self::for_week(year, week)?;
+        timetable.set_weeks(weeks);
+        Ok(timetable)
+    }
+    ///length of the planning horizon; weekly bounds apply to each week
+    pub fn set_weeks(&mut self, weeks: usize) -> &mut Self {
+        self.weeks = weeks.max(1);
+        self
+    }
+    pub fn weeks(&self) -> usize {
+        self.weeks
+    }
+    pub fn set_week(&mut self, year: i32, week: u32) -> Result<&mut Self, ScheduleError> {
+        let monday = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)
+            .ok_or(ScheduleError::InvalidWeek { year, week })?;
+        self.week_start = Some(monday);
+        Ok(self)
+    }
+    pub fn week_start(&self) -> Option<NaiveDate> {
+        self.week_start
+    }
+    ///local closure, e.g. Closure::Yearly(1, 20) for the patron saint's day
+    pub fn add_closure(&mut self, closure: Closure, name: &str) -> &mut Self {
+        self.closures.push((closure, name.to_string()));
+        self
+    }
+    pub fn closures(&self) -> &[(Closure, String)] {
+        &self.closures
+    }
+    ///date of an open day (position in days) of a week of the horizon, when scheduling concrete weeks
+    pub fn date_of(&self, week: usize, day: usize) -> Option<NaiveDate> {
+        self.week_start?.checked_add_days(Days::new(week as u64 * 7 + self.days[day] as u64))
+    }
+    ///why an open day (position in days) is closed in a week of the horizon, if it is
+    pub fn closure_of(&self, week: usize, day: usize) -> Option<String> {
+        let date = self.date_of(week, day)?;
+        if let Some(name) = calendar::italian_holiday(date) {
+            return Some(name.to_string());
         }
+        self.closures.iter()
+            .find(|(c, _)| c.matches(date))
+            .map(|(_, name)| name.clone())
+    }
+    ///day name, followed by its date when scheduling concrete weeks
+    ///or preceded by the week number in an abstract horizon
+    pub fn day_label(&self, week: usize, day: usize) -> String {
+        match self.date_of(week, day) {
+            Some(date) => format!("{} {}", self.days[day].name(), date),
+            None if self.weeks > 1 => format!("W{} {}", week + 1, self.days[day].name()),
+            None => self.days[day].name().to_string(),
+        }
+    }
+    pub fn days(&self) -> &[Day] {
+        &self.days
+    }
+    ///position of the day in days, None when closed
+    pub fn day_index(&self, day: Day) -> Option<usize> {
+        self.days.iter().position(|d| *d == day)
+    }
+    ///opens (or changes the hours of) a day, e.g. Saturday mornings in spring.
+    ///Use TurnHours(0.0) for the turns that stay closed
+    pub fn open_day(&mut self, day: Day, hours: Vec<TurnHours>) -> Result<&mut Self, ScheduleError> {
+        if hours.len() != self.turns.len() {
+            return Err(ScheduleError::DimensionMismatch { what: "shifts", expected: self.turns.len(), found: hours.len() });
+        }
+        match self.day_index(day) {
+            Some(d) => self.base[d] = hours,
+            None => {
+                let d = self.days.iter().position(|open| *open > day).unwrap_or(self.days.len());
+                self.days.insert(d, day);
+                self.base.insert(d, hours);
+            }
+        }
+        Ok(self)
+    }
+    pub fn close_day(&mut self, day: Day) -> &mut Self {
+        if let Some(d) = self.day_index(day) {
+            self.days.remove(d);
+            self.base.remove(d);
+        }
+        self
+    }
+    ///human readable name of a shift
+    pub fn shift_label(&self, week: usize, day: usize, turn: usize) -> String {
+        format!("{} {}", self.day_label(week, day), self.turns[turn].name)
+    }
+    ///adds a turn with its full length to every day
+    pub fn add_turn(&mut self, turn: TurnDef) -> Turn {
+        for day in self.base.iter_mut() {
+            day.push(TurnHours(turn.hours() as f32));
+        }
+        self.turns.push(turn);
+        Turn(self.turns.len() - 1)
+    }
+    pub fn turns(&self) -> &[TurnDef] {
+        &self.turns
+    }
+    ///turn with the given name, in any case
+    pub fn turn_by_name(&self, name: &str) -> Option<Turn> {
+        let name = name.trim();
+        self.turns.iter().position(|t| t.name.eq_ignore_ascii_case(name)).map(Turn)
+    }
+    ///end of a turn on a day, which can be earlier than TurnDef::end (e.g. on Fridays)
+    pub fn turn_end(&self, day: usize, turn: usize) -> ClockTime {
+        self.turns[turn].start.add_hours(self.base[day][turn].hours())
+    }
+    pub fn set_config(&mut self, config: TimetableConfig) -> &mut Self {
+        self.config = config;
+        self
     }
     pub fn add_person(&mut self, p: Person) -> &mut Self {
         self.people.push(p);
@@ -80,54 +388,342 @@ impl Timetable {
         self
     }
 
-    pub fn set_turnhours(&mut self, turnhours: Vec<(TurnHours, TurnHours)>) -> &mut Self {
+    ///hours per [day][turn], one row per open day and one column per turn
+    pub fn set_turnhours(&mut self, turnhours: Vec<Vec<TurnHours>>) -> Result<&mut Self, ScheduleError> {
+        if turnhours.len() != self.days.len() {
+            return Err(ScheduleError::DimensionMismatch { what: "days", expected: self.days.len(), found: turnhours.len() });
+        }
+        if let Some(day) = turnhours.iter().find(|day| day.len() != self.turns.len()) {
+            return Err(ScheduleError::DimensionMismatch { what: "shifts", expected: self.turns.len(), found: day.len() });
+        }
         self.base = turnhours;
+        Ok(self)
+    }
+    ///hours per [day][turn] of a week without holidays
+    pub fn turnhours(&self) -> &[Vec<TurnHours>] {
+        &self.base
+    }
+    pub fn add_constraint(&mut self, constraint: impl ScheduleConstraint + 'static) -> &mut Self {
+        self.constraints.push(Rc::new(constraint));
         self
     }
-    pub fn calc(&mut self) -> &mut Self {
-        //SETUP MODEL
-        let mut scheduler = Scheduler::new(12,
-                                           1,
-                                           7,
-                                           5,
-                                           2);
+    ///people needed on a turn, e.g. two in the afternoon during exams or zero on a closed morning
+    pub fn set_coverage(&mut self, day: Day, turn: Turn, coverage: Coverage) -> &mut Self {
+        self.coverage.retain(|(d, t, _)| *d != day || *t != turn);
+        self.coverage.push((day, turn, coverage));
+        self
+    }
+    ///headcount of the turns that differ from Coverage::default()
+    pub fn custom_coverage(&self) -> &[(Day, Turn, Coverage)] {
+        &self.coverage
+    }
+    ///function minimised by the next calc
+    pub fn set_objective(&mut self, objective: Objective) -> &mut Self {
+        self.objective = Some(objective);
+        self
+    }
+    ///the objective set by the user or, by default, the remaining-hours preference
+    ///plus cumulative fairness when planning more than a week
+    ///deadline urgency when somebody has a contract deadline
+    ///and week hours target when somebody asked for one.
+    ///An allowed overrun is always penalised
+    pub fn objective(&self) -> Result<Objective, ScheduleError> {
+        if let Some(objective) = &self.objective {
+            return Ok(objective.clone());
+        }
+        let mut objective = Objective::default();
+        //same order of magnitude as the remaining-hours weighted term
+        let weight = self.people.iter()
+            .map(|p| p.tot_hours - p.worked_hours)
+            .fold(1.0, f64::max);
+        if self.weeks > 1 {
+            objective.add_term(weight, CumulativeFairness);
+        }
+        if self.config.max_overrun_hours > 0.0 {
+            objective.add_term(weight, OverrunPenalty);
+        }
+        if self.people.iter().any(|p| p.target_week_hours.is_some()) {
+            objective.add_term(weight, WeekHoursTarget);
+        }
+        let report = self.deadline_report()?;
+        if !report.is_empty() {
+            let mut pace = vec![0.0; self.people.len()];
+            for status in report.iter() {
+                pace[status.person] = status.pace();
+            }
+            objective.add_term(weight, DeadlineUrgency::new(pace));
+        }
+        Ok(objective)
+    }
+    ///constraints enabled for the next calc
+    fn enabled_constraints(&self) -> Vec<Rc<dyn ScheduleConstraint>> {
+        let mut constraints: Vec<Rc<dyn ScheduleConstraint>> = vec![
+            Rc::new(RespectUnavailability),
+            Rc::new(ShiftCoverage),
+            Rc::new(MinMaxWeekHoursPerPerson(self.config.min_hours_per_week,
+                                             self.config.max_hours_per_week)),
+            Rc::new(RemainingHoursCap { max_overrun: self.config.max_overrun_hours }),
+            // Rc::new(NoConsecutiveShifts),
+        ];
+        constraints.extend(self.constraints.iter().cloned());
+        constraints
+    }
 
-        scheduler.setup(&self);
-        //CHECK FOR ERRORS/BAD SETUP
+    ///names of the constraint groups enabled for the next calc
+    pub fn constraint_names(&self) -> Vec<&'static str> {
+        self.enabled_constraints().iter().map(|c| c.name()).collect()
+    }
 
-        //ADD CONSTRAINTS (enabled ones)
-        scheduler.set_constraint(ConstraintType::MaxOnePersonPerShift);
-        scheduler.set_constraint(ConstraintType::MinMaxWeekHoursPerPerson(1.0, 12.0));
-        // scheduler.set_constraint(ConstraintType::NoConsecutiveShifts);
+    fn build_scheduler(&self, constraints: &[Rc<dyn ScheduleConstraint>]) -> Result<Scheduler, ScheduleError> {
+        //SETUP MODEL
+        let mut scheduler = Scheduler::new();
+        scheduler.setup(self)?;
 
+        //ADD CONSTRAINTS
+        for c in constraints.iter() {
+            scheduler.set_constraint(c.as_ref());
+        }
+        scheduler.set_objective(&self.objective()?);
+        Ok(scheduler)
+    }
+
+    fn solve_with(&self, constraints: &[Rc<dyn ScheduleConstraint>]) -> Result<ScheduleResult, ScheduleError> {
         //RUN SCHEDULER
-        scheduler.schedule();
+        self.build_scheduler(constraints)?.schedule()
+    }
 
+    pub fn calc(&mut self) -> Result<&mut Self, ScheduleError> {
+        let constraints = self.enabled_constraints();
+        let result = match self.solve_with(&constraints) {
+            Ok(result) => result,
+            Err(ScheduleError::Infeasible(_)) => {
+                return Err(ScheduleError::Infeasible(self.explain_infeasibility(&constraints)));
+            }
+            Err(e) => return Err(e),
+        };
 
+        //STORE RESULTS
+        Ok(self.set_result(result))
+    }
+
+    ///pre-solve checks of the people preferences against shifts and hour bounds
+    pub fn diagnose(&self) -> Result<Vec<Issue>, ScheduleError> {
+        let (preferences, _) = self.get_people_preferences_and_rem_hours()?;
+        let unavailable = self.get_people_unavailability()?;
+        let labels: Vec<Vec<Vec<String>>> = (0..self.weeks)
+            .map(|w| (0..self.days.len())
+                .map(|d| (0..self.turns.len()).map(|s| self.shift_label(w, d, s)).collect())
+                .collect())
+            .collect();
+        let mut issues = diagnosis::analyze(&self.people, &preferences, &unavailable, &labels,
+                                            &self.get_turn_hours(), &self.get_coverage()?, &self.config);
+        for status in self.deadline_report()?.into_iter().filter(|s| !s.is_reachable()) {
+            let person = &self.people[status.person];
+            issues.push(Issue::UnreachableDeadline {
+                person: status.person,
+                name: format!("{} {}", person.name, person.surname),
+                deadline: status.deadline,
+                remaining_hours: status.remaining_hours,
+                capacity_hours: status.capacity_hours,
+            });
+        }
+        Ok(issues)
+    }
 
-        //STORE RESULTS IF ALL GOOD ELSE SIGNAL IT
+    ///for each person with a contract deadline, the hours left against the open shifts
+    ///from the first planned week to the deadline. Deadlines need a concrete ISO week to be checked
+    pub fn deadline_report(&self) -> Result<Vec<DeadlineStatus>, ScheduleError> {
+        if self.people.iter().all(|p| p.deadline.is_none()) {
+            return Ok(vec![]);
+        }
+        let Some(start) = self.week_start else {
+            return Err(ScheduleError::Missing("dates of the shifts for the contract deadlines, schedule a concrete ISO week"));
+        };
+        let unavailable = self.get_people_unavailability()?;
+        let coverage = self.week_coverage()?;
+        let mut report = vec![];
+        for (i, person) in self.people.iter().enumerate() {
+            let Some(deadline) = person.deadline else {
+                continue;
+            };
+            let (_, max_hours) = person.week_hours_bounds(self.config.min_hours_per_week,
+                                                          self.config.max_hours_per_week);
+            let (mut capacity_hours, mut weeks_left) = (0.0, 0);
+            let mut w = 0;
+            while start + Days::new(w as u64 * 7) <= deadline {
+                let mut week_hours = 0.0;
+                let mut open = false;
+                for d in 0..self.days.len() {
+                    let closed = self.closure_of(w, d).is_some();
+                    if closed || self.date_of(w, d).is_none_or(|date| date > deadline) {
+                        continue;
+                    }
+                    for (s, h) in self.base[d].iter().enumerate() {
+                        if h.hours() > 0.0 && coverage[d][s].max > 0 {
+                            open = true;
+                            if !unavailable[i][d][s] {
+                                week_hours += h.hours();
+                            }
+                        }
+                    }
+                }
+                if open {
+                    weeks_left += 1;
+                    capacity_hours += f64::min(week_hours, max_hours);
+                }
+                w += 1;
+            }
+            report.push(DeadlineStatus {
+                person: i,
+                deadline,
+                remaining_hours: person.tot_hours - person.worked_hours,
+                capacity_hours,
+                weeks_left,
+            });
+        }
+        Ok(report)
+    }
 
+    //a constraint group conflicts with the others if dropping it makes the model feasible
+    fn explain_infeasibility(&self, constraints: &[Rc<dyn ScheduleConstraint>]) -> Diagnosis {
+        let mut conflicts = vec![];
+        for (skip, c) in constraints.iter().enumerate() {
+            let others: Vec<Rc<dyn ScheduleConstraint>> = constraints.iter().enumerate()
+                .filter(|(i, _)| *i != skip)
+                .map(|(_, c)| c.clone())
+                .collect();
+            if self.solve_with(&others).is_ok() {
+                conflicts.push(c.name());
+            }
+        }
+        if conflicts.is_empty() {
+            //no single group is to blame: all of them together are
+            conflicts = constraints.iter().map(|c| c.name()).collect();
+        }
+        //the issues are only a hint, failing to find them must not hide the infeasibility
+        Diagnosis { issues: self.diagnose().unwrap_or_default(), conflicts }
+    }
 
+    ///stores a result computed elsewhere, e.g. read back from a JSON output
+    pub fn set_result(&mut self, result: ScheduleResult) -> &mut Self {
+        self.computed = Some(result.assignments);
+        self.stats = Some(result.stats);
         self
     }
+    ///people assigned to each [week][day][turn], available after a successful calc
+    pub fn computed(&self) -> Option<&Vec<Vec<Vec<Vec<usize>>>>> {
+        self.computed.as_ref()
+    }
 
-    pub fn get_people_preferences_and_rem_hours(&self) -> (Vec<Vec<Vec<bool>>>, Vec<f64>, ) {
-        self.people.iter().map(|p| {
+    pub fn stats(&self) -> Option<&ScheduleStats> {
+        self.stats.as_ref()
+    }
+
+    ///grid of the computed weeks and hours of every person, colored on a terminal
+    pub fn print_calendar(&self) -> Result<(), ScheduleError> {
+        terminal::print(self)
+    }
+
+    ///[person][day][turn] preference weights (0 when not expressed) and remaining hours per person
+    pub fn get_people_preferences_and_rem_hours(&self) -> Result<(PreferenceGrid, Vec<f64>), ScheduleError> {
+        self.people.iter().enumerate().map(|(index, p)| {
+            p.validate().map_err(|reason| ScheduleError::InvalidPerson { index, reason })?;
+            self.check_week_hours(p).map_err(|reason| ScheduleError::InvalidPerson { index, reason })?;
             let rem = p.tot_hours - p.worked_hours;
-            let mut pref_def = vec![vec![false; 2]; 5];
-            p.preferences.iter().for_each(|pre| {
-                let d = pre.day as usize; //MON => 0, ...
-                let s = pre.turn as usize; //morning=>0, afternoon=>1
-                println!("d{d}.s{s}");
-                assert!(d >= 0 && d < 5);
-                assert!(s <= 1);
-                pref_def[d][s] = true;
-            });
-            return (pref_def, rem);
-        }).fold((vec![], vec![]), |mut acc, (pref, rem)| {
+            let slots: Vec<(Day, Turn, f64)> = p.preferences.iter().map(|pre| (pre.day, pre.turn, pre.weight)).collect();
+            Ok((self.slots_to_grid(index, p, &slots)?, rem))
+        }).try_fold((vec![], vec![]), |mut acc, res: Result<_, ScheduleError>| {
+            let (pref, rem) = res?;
             acc.0.push(pref);
             acc.1.push(rem);
-            acc
+            Ok(acc)
         })
     }
-}
\ No newline at end of file
+
+    //the bounds of the person on top of the config ones, which Person::validate cannot see
+    fn check_week_hours(&self, p: &Person) -> Result<(), String> {
+        let (min, max) = p.week_hours_bounds(self.config.min_hours_per_week, self.config.max_hours_per_week);
+        if min > max {
+            return Err(format!("{}: weekly minimum {} above maximum {}", p.acronym(), min, max));
+        }
+        match p.target_week_hours {
+            Some(target) if target < min || target > max =>
+                Err(format!("{}: weekly target {} outside {} - {}", p.acronym(), target, min, max)),
+            _ => Ok(()),
+        }
+    }
+
+    ///[person][day][turn] set when the person cannot work that shift
+    pub fn get_people_unavailability(&self) -> Result<Vec<Vec<Vec<bool>>>, ScheduleError> {
+        self.people.iter().enumerate().map(|(index, p)| {
+            let slots: Vec<(Day, Turn, bool)> = p.unavailable.iter().map(|u| (u.day, u.turn, true)).collect();
+            self.slots_to_grid(index, p, &slots)
+        }).collect()
+    }
+
+    fn slots_to_grid<T: Copy + Default>(&self, index: usize, p: &Person, slots: &[(Day, Turn, T)])
+                                        -> Result<Vec<Vec<T>>, ScheduleError> {
+        let num_turns = self.turns.len();
+        let mut grid = vec![vec![T::default(); num_turns]; self.days.len()];
+        for (day, turn, value) in slots.iter() {
+            //slots on closed days do not matter
+            let Some(d) = self.day_index(*day) else {
+                continue;
+            };
+            let s = turn.0;
+            if s >= num_turns {
+                return Err(ScheduleError::InvalidPerson {
+                    index,
+                    reason: format!("{}: turn #{} is not in the timetable ({} turns)",
+                                    p.acronym(), s, num_turns),
+                });
+            }
+            grid[d][s] = *value;
+        }
+        Ok(grid)
+    }
+
+    ///headcount of each turn, indexed as [week][day][turn]
+    pub fn get_coverage(&self) -> Result<Vec<Vec<Vec<Coverage>>>, ScheduleError> {
+        let mut coverage = vec![self.week_coverage()?; self.weeks];
+        //nobody works a turn that is closed that day
+        for (w, days) in self.get_turn_hours().iter().enumerate() {
+            for (d, turns) in days.iter().enumerate() {
+                for (s, h) in turns.iter().enumerate() {
+                    if *h <= 0.0 && s < self.turns.len() {
+                        coverage[w][d][s] = Coverage::exactly(0);
+                    }
+                }
+            }
+        }
+        Ok(coverage)
+    }
+
+    //headcount of each turn of a week without holidays, indexed as [day][turn]
+    fn week_coverage(&self) -> Result<Vec<Vec<Coverage>>, ScheduleError> {
+        let mut week = vec![vec![Coverage::default(); self.turns.len()]; self.base.len()];
+        for (day, turn, c) in self.coverage.iter() {
+            if turn.0 >= self.turns.len() || c.min > c.max {
+                return Err(ScheduleError::InvalidCoverage { day: *day, turn: turn.0, coverage: *c });
+            }
+            //closed days need nobody
+            if let Some(d) = self.day_index(*day) {
+                week[d][turn.0] = *c;
+            }
+        }
+        Ok(week)
+    }
+
+    ///hours of each turn, indexed as [week][day][turn] like the scheduler variables.
+    ///Days closed for holidays have all turns at 0
+    pub fn get_turn_hours(&self) -> Vec<Vec<Vec<f64>>> {
+        (0..self.weeks).map(|w| {
+            self.base.iter().enumerate()
+                .map(|(d, turns)| {
+                    let closed = self.closure_of(w, d).is_some();
+                    turns.iter().map(|h| if closed { 0.0 } else { h.hours() }).collect()
+                })
+                .collect()
+        }).collect()
+    }
+}